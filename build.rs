@@ -1,11 +1,25 @@
+// the build script cannot depend on its own package, so the data structs are included directly
+#[allow(dead_code)]
+#[path = "src/data/stats.rs"]
+mod stats;
+
+#[allow(dead_code)]
 #[path = "src/data/structs.rs"]
 mod structs;
 
+#[allow(dead_code)]
 #[path = "src/util.rs"]
 mod util;
 
-use std::{env, fs, path::PathBuf};
-use structs::DefData;
+use std::{
+    collections::BTreeMap,
+    env, fmt, fs,
+    path::{Path, PathBuf},
+};
+
+// included structs import their sibling modules via `super`
+use stats::*;
+use structs::{BuffData, CategoryData, DefData, IgnoreName};
 use util::parse_jsonc;
 
 const DEF_PATH: &str = "src/data/definitions.json";
@@ -13,16 +27,188 @@ const DEF_PATH: &str = "src/data/definitions.json";
 fn main() {
     let manifest = env::var_os("CARGO_MANIFEST_DIR").unwrap();
 
-    // parse default definitions
+    // parse default definitions, including stat modifiers
     println!("cargo:rerun-if-changed={DEF_PATH}");
     let content = fs::read_to_string(PathBuf::from(manifest).join(DEF_PATH))
         .expect("failed to read definitions");
-    let mut defs: DefData = parse_jsonc(&content).expect("failed to parse definitions");
+    let defs: DefData = parse_jsonc(&content).expect("failed to parse definitions");
+
+    // save data as static table
+    let out = env::var_os("OUT_DIR").unwrap();
+    fs::write(Path::new(&out).join("definitions.rs"), table(defs))
+        .expect("failed to write definitions data");
+}
+
+/// Generates the static default table expression.
+fn table(defs: DefData) -> String {
+    // entries sorted alphabetically, ignore entries have no name
+    let mut entries = defs
+        .food
+        .iter()
+        .map(|data| {
+            (
+                data.id,
+                data.name.as_ref(),
+                &*data.aliases,
+                format!("DefinitionKind::Food({})", buff(data)),
+            )
+        })
+        .chain(defs.utility.iter().map(|data| {
+            (
+                data.id,
+                data.name.as_ref(),
+                &*data.aliases,
+                format!("DefinitionKind::Util({})", buff(data)),
+            )
+        }))
+        .chain(defs.categories.iter().flat_map(|category| {
+            category.buffs.iter().map(|data| {
+                (
+                    data.id,
+                    data.name.as_ref(),
+                    &*data.aliases,
+                    format!(
+                        "DefinitionKind::Category({}, {})",
+                        borrowed(&category.name),
+                        buff(data)
+                    ),
+                )
+            })
+        }))
+        .chain(defs.ignore.iter().map(|data| {
+            let reason = option(data.reason.as_deref().map(borrowed));
+            (
+                data.id,
+                "",
+                [].as_slice(),
+                format!("DefinitionKind::Ignore({reason})"),
+            )
+        }))
+        .collect::<Vec<_>>();
+    entries.sort_by(|a, b| a.1.cmp(b.1));
+
+    // sorted by id, ids with their own entry take precedence over aliases
+    let mut index = BTreeMap::new();
+    for (i, (id, ..)) in entries.iter().enumerate() {
+        index.insert(*id, i);
+    }
+    for (i, (_, _, aliases, _)) in entries.iter().enumerate() {
+        for alias in *aliases {
+            index.entry(*alias).or_insert(i);
+        }
+    }
 
-    // sort alphabetically
-    defs.food.sort_by(|a, b| a.name.cmp(&b.name));
-    defs.utility.sort_by(|a, b| a.name.cmp(&b.name));
+    let entries = entries
+        .iter()
+        .map(|(id, _, _, def)| {
+            format!("DefinitionEntry {{ id: {id}, def: {def}, source: DefinitionSource::Default }}")
+        })
+        .collect::<Vec<_>>();
+    let index = index
+        .iter()
+        .map(|(id, pos)| format!("({id}, {pos})"))
+        .collect::<Vec<_>>();
+    let ignore_names = defs
+        .ignore_names
+        .iter()
+        .map(ignore_name)
+        .collect::<Vec<_>>();
+    let categories = defs.categories.iter().map(category).collect::<Vec<_>>();
+
+    format!(
+        "DefaultTable {{ entries: &{}, index: &{}, ignore_names: &{}, categories: &{} }}",
+        list(&entries),
+        list(&index),
+        list(&ignore_names),
+        list(&categories)
+    )
+}
+
+/// Generates a buff data expression.
+fn buff(data: &BuffData) -> String {
+    let names = data
+        .names
+        .iter()
+        .map(|(code, name)| format!("({}, {})", borrowed(code), borrowed(name)))
+        .collect::<Vec<_>>();
+    let stats = data.stats.iter().map(stat).collect::<Vec<_>>();
+    let aliases = data.aliases.iter().map(u32::to_string).collect::<Vec<_>>();
+
+    format!(
+        "BuffData {{ id: {}, name: {}, names: LocalizedNames(Cow::Borrowed(&{})), stats: Cow::Borrowed(&{}), display: {}, rarity: Rarity::{:?}, duration: {}, item: {}, aliases: Cow::Borrowed(&{}) }}",
+        data.id,
+        borrowed(&data.name),
+        list(&names),
+        list(&stats),
+        borrowed(&data.display),
+        data.rarity,
+        option(data.duration),
+        option(data.item),
+        list(&aliases),
+    )
+}
+
+/// Generates a stat modifier expression.
+fn stat(stat: &StatModifier) -> String {
+    match stat {
+        StatModifier::Attribute {
+            attribute,
+            amount,
+            signed,
+        } => format!(
+            "StatModifier::Attribute {{ attribute: Attribute::{attribute:?}, amount: {amount}, signed: {signed} }}"
+        ),
+        StatModifier::Conversion {
+            attribute,
+            source,
+            percent,
+        } => format!(
+            "StatModifier::Conversion {{ attribute: Attribute::{attribute:?}, source: Attribute::{source:?}, percent: {percent:?} }}"
+        ),
+        StatModifier::Percent {
+            effect,
+            percent,
+            signed,
+        } => format!(
+            "StatModifier::Percent {{ effect: {}, percent: {percent:?}, signed: {signed} }}",
+            borrowed(effect)
+        ),
+        StatModifier::Other(text) => format!("StatModifier::Other({})", borrowed(text)),
+    }
+}
+
+/// Generates an ignore name rule expression.
+fn ignore_name(rule: &IgnoreName) -> String {
+    format!(
+        "IgnoreName {{ name: {}, reason: {} }}",
+        borrowed(&rule.name),
+        option(rule.reason.as_deref().map(borrowed))
+    )
+}
+
+/// Generates a category declaration expression.
+fn category(category: &CategoryData) -> String {
+    format!(
+        "Category {{ name: {}, display: {} }}",
+        borrowed(&category.name),
+        option(category.display.as_deref().map(borrowed))
+    )
+}
+
+/// Generates a borrowed string expression.
+fn borrowed(text: &str) -> String {
+    format!("Cow::Borrowed({text:?})")
+}
+
+/// Generates an option expression.
+fn option(value: Option<impl fmt::Display>) -> String {
+    match value {
+        Some(value) => format!("Some({value})"),
+        None => "None".into(),
+    }
+}
 
-    // save data
-    uneval::to_out_dir(defs, "definitions.rs").expect("failed to write definitions data");
+/// Generates a slice literal from element expressions.
+fn list(items: &[String]) -> String {
+    format!("[{}]", items.join(", "))
 }
@@ -0,0 +1,176 @@
+//! Command line tool to import consumables from GW2 API item dumps into definitions files.
+//!
+//! Usage: `cargo run --bin defs_import -- [--known <FILE>] [--write] <FILE> <DUMP>...`
+
+use arcdps_food_reminder::{
+    data::{ApiConsumable, DefData},
+    util::parse_jsonc_value,
+};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+const USAGE: &str = "\
+Usage: defs_import [OPTIONS] <FILE> <DUMP>...
+
+Imports food & utility consumables from saved GW2 API /v2/items dumps into a definitions file.
+Buff ids are looked up by item id or name in the file & the known definitions,
+consumables without known buff id are listed and skipped.
+Status is printed to stderr, the merged definitions to stdout unless written.
+
+Options:
+  --known <FILE>  Definitions to look up buff ids in, e.g. the default definitions
+  --write         Write the merged definitions to the file instead of printing them,
+                  comments are not preserved
+  -h, --help      Print this help";
+
+fn main() -> ExitCode {
+    let mut known_path = None;
+    let mut write = false;
+    let mut files = Vec::new();
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--known" => match args.next() {
+                Some(path) => known_path = Some(PathBuf::from(path)),
+                None => {
+                    eprintln!("Missing file for --known\n\n{USAGE}");
+                    return ExitCode::FAILURE;
+                }
+            },
+            "--write" => write = true,
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return ExitCode::SUCCESS;
+            }
+            _ if arg.starts_with('-') => {
+                eprintln!("Unknown option {arg}\n\n{USAGE}");
+                return ExitCode::FAILURE;
+            }
+            _ => files.push(PathBuf::from(arg)),
+        }
+    }
+
+    let [path, dumps @ ..] = files.as_slice() else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+    if dumps.is_empty() {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    }
+
+    let known = match &known_path {
+        Some(known_path) => match load_file(known_path) {
+            Some((data, _)) => data,
+            None => return ExitCode::FAILURE,
+        },
+        None => DefData::default(),
+    };
+
+    // missing target files are created
+    let (mut data, valid) = if path.exists() {
+        match load_file(path) {
+            Some(result) => result,
+            None => return ExitCode::FAILURE,
+        }
+    } else {
+        (DefData::default(), true)
+    };
+
+    let mut consumables = Vec::new();
+    for dump in dumps {
+        let file = dump.display();
+        match fs::read_to_string(dump)
+            .map_err(|err| err.to_string())
+            .and_then(|content| ApiConsumable::parse_dump(&content).map_err(|err| err.to_string()))
+        {
+            Ok(items) => {
+                eprintln!("{file}: {} consumables", items.len());
+                consumables.extend(items);
+            }
+            Err(err) => {
+                eprintln!("{file}: {err}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let report = data.import(consumables, &known);
+    let file = path.display();
+    for item in &report.unmatched {
+        eprintln!(
+            "{file}: no buff id for item {} \"{}\", skipped",
+            item.item, item.name
+        );
+    }
+    eprintln!(
+        "{file}: added {}, updated {}, unchanged {}, skipped {}",
+        report.added.len(),
+        report.updated.len(),
+        report.unchanged,
+        report.unmatched.len()
+    );
+
+    let lints = data.lint(false);
+    for lint in &lints {
+        eprintln!("{file}: {lint}");
+    }
+
+    data.sort();
+    let json = data.to_json_pretty() + "\n";
+    if !write {
+        print!("{json}");
+        return ExitCode::SUCCESS;
+    }
+
+    // only rewrite files without skipped entries to avoid losing them
+    if !valid {
+        eprintln!("{file}: not written due to invalid entries");
+        return ExitCode::FAILURE;
+    }
+    match fs::write(path, json) {
+        Ok(()) => {
+            eprintln!("{file}: written");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{file}: failed to write: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Loads a definitions file, printing errors for skipped entries.
+///
+/// Returns the data and whether all entries were valid.
+fn load_file(path: &Path) -> Option<(DefData, bool)> {
+    let file = path.display();
+
+    let result = fs::read_to_string(path)
+        .map_err(|err| format!("failed to read: {err}"))
+        .and_then(|content| {
+            let value = parse_jsonc_value(&content).map_err(|err| err.to_string())?;
+            let (data, mut errors) =
+                DefData::from_value_lenient(value).map_err(|err| err.to_string())?;
+            for err in &mut errors {
+                err.locate(&content);
+            }
+            Ok((data, errors))
+        });
+    match result {
+        Ok((data, errors)) => {
+            for err in &errors {
+                eprintln!("{file}: {err}");
+            }
+            Some((data, errors.is_empty()))
+        }
+        Err(err) => {
+            eprintln!("{file}: {err}");
+            None
+        }
+    }
+}
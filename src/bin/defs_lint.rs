@@ -0,0 +1,144 @@
+//! Command line tool to check & format definitions files.
+//!
+//! Usage: `cargo run --bin defs_lint -- [--defaults] [--write] <FILE>...`
+
+use arcdps_food_reminder::{
+    data::{DefData, Migration},
+    util::{parse_jsonc_value, JsonError},
+};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+const USAGE: &str = "\
+Usage: defs_lint [OPTIONS] <FILE>...
+
+Checks definitions files for invalid entries, duplicate ids & long display names.
+
+Options:
+  --defaults  Require the entries needed in the default definitions
+  --write     Rewrite valid files sorted, formatted & upgraded to the current format version,
+              comments are not preserved
+  -h, --help  Print this help";
+
+fn main() -> ExitCode {
+    let mut defaults = false;
+    let mut write = false;
+    let mut files = Vec::new();
+
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--defaults" => defaults = true,
+            "--write" => write = true,
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return ExitCode::SUCCESS;
+            }
+            _ if arg.starts_with('-') => {
+                eprintln!("Unknown option {arg}\n\n{USAGE}");
+                return ExitCode::FAILURE;
+            }
+            _ => files.push(PathBuf::from(arg)),
+        }
+    }
+
+    if files.is_empty() {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    }
+
+    let mut valid = true;
+    for path in &files {
+        valid &= check_file(path, defaults, write);
+    }
+
+    if valid {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Checks a definitions file and optionally formats it.
+///
+/// Returns `true` if the file is valid.
+fn check_file(path: &Path, defaults: bool, write: bool) -> bool {
+    let file = path.display();
+
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) => {
+            eprintln!("{file}: failed to read: {err}");
+            return false;
+        }
+    };
+
+    // parse with precise locations
+    let mut value = match parse_jsonc_value(&content) {
+        Ok(value) => value,
+        Err(JsonError::Syntax {
+            line,
+            column,
+            message,
+        }) => {
+            eprintln!("{file}:{line}:{column}: {message}");
+            return false;
+        }
+        Err(err) => {
+            eprintln!("{file}: {err}");
+            return false;
+        }
+    };
+    match Migration::apply(&mut value) {
+        Ok(migration) if migration.upgraded() || migration.is_newer() => {
+            println!("{file}: {migration}")
+        }
+        Ok(_) => {}
+        Err(err) => {
+            eprintln!("{file}: {err}");
+            return false;
+        }
+    }
+    let (mut data, mut errors) = match DefData::from_value_lenient(value) {
+        Ok(result) => result,
+        Err(err) => {
+            eprintln!("{file}: {err}");
+            return false;
+        }
+    };
+    for err in &mut errors {
+        err.locate(&content);
+        eprintln!("{file}: {err}");
+    }
+
+    let lints = data.lint(defaults);
+    for lint in &lints {
+        eprintln!("{file}: {lint}");
+    }
+
+    // only rewrite files without skipped entries to avoid losing them
+    if write {
+        if errors.is_empty() {
+            data.sort();
+            if let Err(err) = fs::write(path, data.to_json_pretty() + "\n") {
+                eprintln!("{file}: failed to write: {err}");
+                return false;
+            }
+            println!("{file}: formatted");
+        } else {
+            eprintln!("{file}: not formatted due to invalid entries");
+        }
+    }
+
+    let valid = errors.is_empty() && lints.is_empty();
+    if valid {
+        let count = data.food.len()
+            + data.utility.len()
+            + data.category_buffs().count()
+            + data.ignore.len();
+        println!("{file}: ok, {count} entries");
+    }
+    valid
+}
@@ -1,20 +1,76 @@
-use crate::data::BuffData;
+use crate::{
+    data::{BuffData, Definitions, Language, SkillNames, Slot},
+    reminder::custom::CustomReminder,
+    tracking::uptime::{format_percent, UptimeStats},
+};
 use arc_util::ui::{render, Ui};
 use arcdps::{
     exports::{self, CoreColor},
     imgui::{Selectable, StyleColor},
 };
 
+/// Formats a remaining time in milliseconds for display.
+pub fn format_remaining(remaining: u64) -> String {
+    let secs = remaining / 1000;
+    if secs >= 60 {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{secs}s")
+    }
+}
+
+/// Renders uptime stats, usually inside a tooltip.
+pub fn render_uptime_tooltip<'a>(
+    ui: &Ui,
+    title: &str,
+    stats: &UptimeStats,
+    defs: &Definitions,
+    custom: impl IntoIterator<Item = &'a CustomReminder>,
+    names: &SkillNames,
+) {
+    ui.text(format!("{title} ({})", format_remaining(stats.duration)));
+    ui.text(format!("Food: {}", format_percent(stats.food_percent())));
+    ui.text(format!("Utility: {}", format_percent(stats.util_percent())));
+    for category in defs.categories() {
+        ui.text(format!(
+            "{}: {}",
+            category.name,
+            format_percent(stats.category_percent(&category.name))
+        ));
+    }
+    for remind in custom {
+        ui.text(format!(
+            "{}: {}",
+            remind.resolved_name(names),
+            format_percent(stats.custom_percent(remind.id))
+        ));
+    }
+}
+
 /// Renders a tooltip for a buff.
-pub fn render_buff_tooltip(ui: &Ui, buff: &BuffData) {
+pub fn render_buff_tooltip(ui: &Ui, buff: &BuffData, language: Language) {
+    render_tracked_buff_tooltip(ui, buff, language, None)
+}
+
+/// Renders a tooltip for a tracked buff with an optional predicted remaining time.
+pub fn render_tracked_buff_tooltip(
+    ui: &Ui,
+    buff: &BuffData,
+    language: Language,
+    remaining: Option<u64>,
+) {
     if ui.is_item_hovered() {
         ui.tooltip(|| {
+            let name = buff.name_in(language);
             match buff.rarity.color() {
-                Some(color) => ui.text_colored(color, &buff.name),
-                None => ui.text(&buff.name),
+                Some(color) => ui.text_colored(color, name),
+                None => ui.text(name),
+            }
+            for stat in buff.stats.iter() {
+                ui.text(stat.to_string());
             }
-            if !buff.stats.is_empty() {
-                ui.text(buff.stats.join("\n"));
+            if let Some(remaining) = remaining {
+                ui.text_disabled(format!("{} remaining", format_remaining(remaining)));
             }
         });
     }
@@ -26,7 +82,8 @@ pub fn render_buff_context_menu(
     menu_id: impl Into<String>,
     title: &str,
     buff_id: u32,
-    name: Option<&str>,
+    buff: Option<&BuffData>,
+    language: Language,
     colors: &exports::Colors,
 ) {
     render::item_context_menu(menu_id, || {
@@ -34,14 +91,17 @@ pub fn render_buff_context_menu(
             Some(color) => ui.text_colored(color, title),
             None => ui.text(title),
         }
-        if let Some(name) = name {
+        if let Some(buff) = buff {
             if ui.small_button("Copy name") {
-                ui.set_clipboard_text(name);
+                ui.set_clipboard_text(buff.name_in(language));
+            }
+            if let Some(link) = buff.chat_link() {
+                if ui.small_button("Copy chat link") {
+                    ui.set_clipboard_text(link);
+                }
             }
             if ui.small_button("Open wiki") {
-                let _ = open::that(format!(
-                    "https://wiki-en.guildwars2.com/wiki/Special:Search/{name}"
-                ));
+                let _ = open::that(buff.wiki_url(language));
             }
         }
         if ui.small_button("Copy id") {
@@ -55,7 +115,8 @@ pub fn render_food_context_menu(
     ui: &Ui,
     menu_id: usize,
     buff_id: u32,
-    name: Option<&str>,
+    buff: Option<&BuffData>,
+    language: Language,
     colors: &exports::Colors,
 ) {
     render_buff_context_menu(
@@ -63,7 +124,8 @@ pub fn render_food_context_menu(
         format!("##food-context-{menu_id}"),
         "Food options",
         buff_id,
-        name,
+        buff,
+        language,
         colors,
     )
 }
@@ -73,7 +135,8 @@ pub fn render_util_context_menu(
     ui: &Ui,
     menu_id: usize,
     buff_id: u32,
-    name: Option<&str>,
+    buff: Option<&BuffData>,
+    language: Language,
     colors: &exports::Colors,
 ) {
     render_buff_context_menu(
@@ -81,22 +144,49 @@ pub fn render_util_context_menu(
         format!("##util-context-{menu_id}"),
         "Utility options",
         buff_id,
-        name,
+        buff,
+        language,
         colors,
     )
 }
 
+/// Renders a context menu for an item in a consumable slot.
+pub fn render_slot_context_menu(
+    ui: &Ui,
+    slot: Slot,
+    menu_id: usize,
+    buff_id: u32,
+    buff: Option<&BuffData>,
+    language: Language,
+    colors: &exports::Colors,
+) {
+    match slot {
+        Slot::Food => render_food_context_menu(ui, menu_id, buff_id, buff, language, colors),
+        Slot::Util => render_util_context_menu(ui, menu_id, buff_id, buff, language, colors),
+        Slot::Category(name) => render_buff_context_menu(
+            ui,
+            format!("##{name}-context-{menu_id}"),
+            &format!("{name} options"),
+            buff_id,
+            buff,
+            language,
+            colors,
+        ),
+    }
+}
+
 /// Renders a combo for buffs.
 pub fn render_buff_combo<'b>(
     ui: &Ui,
     label: impl AsRef<str>,
     selected_id: u32,
     buffs: impl Iterator<Item = &'b BuffData> + Clone,
+    language: Language,
 ) -> Option<&'b BuffData> {
     let preview = buffs
         .clone()
         .find(|entry| entry.id == selected_id)
-        .map(|buff| buff.name.clone())
+        .map(|buff| buff.name_in(language))
         .unwrap_or_default();
 
     // TODO: search?
@@ -109,7 +199,10 @@ pub fn render_buff_combo<'b>(
                 .rarity
                 .color()
                 .map(|color| ui.push_style_color(StyleColor::Text, color));
-            if Selectable::new(&entry.name).selected(selected).build(ui) {
+            if Selectable::new(entry.name_in(language))
+                .selected(selected)
+                .build(ui)
+            {
                 result = Some(entry);
             }
             drop(style);
@@ -121,7 +214,7 @@ pub fn render_buff_combo<'b>(
 
             // tooltip
             if ui.is_item_hovered() {
-                render_buff_tooltip(ui, entry);
+                render_buff_tooltip(ui, entry, language);
             }
         }
     }
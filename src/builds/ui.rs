@@ -94,18 +94,19 @@ impl Builds {
                     if let Some(DefinitionKind::Food(food)) = defs.definition(build.food) {
                         match current_food {
                             BuffState::Unknown => ui.text(&food.display),
-                            BuffState::Some(id) if id == food.id => {
+                            BuffState::Some(id) if defs.is_same(id, food.id) => {
                                 ui.text_colored(green, &food.display)
                             }
                             _ => ui.text_colored(red, &food.display),
                         }
 
-                        buff_ui::render_buff_tooltip(ui, food);
+                        buff_ui::render_buff_tooltip(ui, food, defs.display_language());
                         buff_ui::render_food_context_menu(
                             ui,
                             i,
                             food.id,
-                            Some(&food.name),
+                            Some(food),
+                            defs.display_language(),
                             &colors,
                         );
                     }
@@ -115,18 +116,19 @@ impl Builds {
                     if let Some(DefinitionKind::Util(util)) = defs.definition(build.util) {
                         match current_util {
                             BuffState::Unknown => ui.text(&util.display),
-                            BuffState::Some(id) if id == util.id => {
+                            BuffState::Some(id) if defs.is_same(id, util.id) => {
                                 ui.text_colored(green, &util.display)
                             }
                             _ => ui.text_colored(red, &util.display),
                         }
 
-                        buff_ui::render_buff_tooltip(ui, util);
+                        buff_ui::render_buff_tooltip(ui, util, defs.display_language());
                         buff_ui::render_util_context_menu(
                             ui,
                             i,
                             util.id,
-                            Some(&util.name),
+                            Some(util),
+                            defs.display_language(),
                             &colors,
                         );
                     }
@@ -185,6 +187,7 @@ impl Builds {
                     format!("##food-{i}"),
                     build.food,
                     defs.all_food(),
+                    defs.display_language(),
                 ) {
                     build.food = changed.id;
                 }
@@ -197,6 +200,7 @@ impl Builds {
                     format!("##util-{i}"),
                     build.util,
                     defs.all_util(),
+                    defs.display_language(),
                 ) {
                     build.util = changed.id;
                 }
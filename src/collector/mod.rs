@@ -0,0 +1,174 @@
+pub mod ui;
+
+use crate::data::{BuffData, DefData, Definitions, Slot, UNKNOWN_BUFF_TEXT};
+use arc_util::settings::HasSettings;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Collector for unknown Food & Utility buffs.
+#[derive(Debug)]
+pub struct Collector {
+    /// Collected unknown buffs.
+    pub entries: Vec<UnknownBuff>,
+
+    /// Selected entries requested to be saved as learned definitions.
+    learned: Option<DefData>,
+}
+
+impl Collector {
+    /// Creates a new collector.
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            learned: None,
+        }
+    }
+
+    /// Records an unknown buff, returning `true` if it was not collected before.
+    pub fn record(
+        &mut self,
+        id: u32,
+        kind: UnknownKind,
+        skill_name: Option<&str>,
+        character: &str,
+    ) -> bool {
+        if self.entries.iter().any(|entry| entry.id == id) {
+            false
+        } else {
+            self.entries
+                .push(UnknownBuff::new(id, kind, skill_name, character));
+            true
+        }
+    }
+
+    /// Removes all entries which are known in the given definitions.
+    pub fn remove_known(&mut self, defs: &Definitions) {
+        self.entries
+            .retain(|entry| defs.definition(entry.id).is_none());
+    }
+
+    /// Removes all selected entries.
+    pub fn remove_selected(&mut self) {
+        self.entries.retain(|entry| !entry.selected);
+    }
+
+    /// Returns whether any entry is selected.
+    pub fn has_selected(&self) -> bool {
+        self.entries.iter().any(|entry| entry.selected)
+    }
+
+    /// Converts the selected entries into definitions data.
+    pub fn selected_data(&self) -> DefData {
+        let mut data = DefData::default();
+        for entry in self.entries.iter().filter(|entry| entry.selected) {
+            match entry.kind {
+                UnknownKind::Food => data.food.push(entry.to_buff_data()),
+                UnknownKind::Util => data.utility.push(entry.to_buff_data()),
+            }
+        }
+        data
+    }
+
+    /// Takes the definitions data requested to be saved as learned definitions.
+    pub fn take_learned(&mut self) -> Option<DefData> {
+        self.learned.take()
+    }
+}
+
+impl Default for Collector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HasSettings for Collector {
+    type Settings = Vec<UnknownBuff>;
+
+    const SETTINGS_ID: &'static str = "collector";
+
+    fn current_settings(&self) -> Self::Settings {
+        self.entries.clone()
+    }
+
+    fn load_settings(&mut self, loaded: Self::Settings) {
+        self.entries = loaded;
+    }
+}
+
+/// Kind of an unknown buff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum UnknownKind {
+    Food,
+    Util,
+}
+
+impl UnknownKind {
+    /// Returns the unknown kind for a consumable slot.
+    ///
+    /// Buffs in consumable categories are always known.
+    pub fn from_slot(slot: Slot) -> Option<Self> {
+        match slot {
+            Slot::Food => Some(Self::Food),
+            Slot::Util => Some(Self::Util),
+            Slot::Category(_) => None,
+        }
+    }
+}
+
+impl AsRef<str> for UnknownKind {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Food => "Food",
+            Self::Util => "Utility",
+        }
+    }
+}
+
+/// Collected unknown buff.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnknownBuff {
+    /// Ingame buff id.
+    pub id: u32,
+
+    /// Kind of buff.
+    pub kind: UnknownKind,
+
+    /// Skill name reported by arcdps.
+    pub skill_name: Option<String>,
+
+    /// Name used when exporting the buff as definition.
+    pub name: String,
+
+    /// Time the buff was first seen as seconds since the unix epoch.
+    pub first_seen: u64,
+
+    /// Character the buff was first seen on.
+    pub character: String,
+
+    /// Whether the entry is selected for export.
+    #[serde(skip)]
+    pub selected: bool,
+}
+
+impl UnknownBuff {
+    /// Creates a new unknown buff first seen now.
+    pub fn new(id: u32, kind: UnknownKind, skill_name: Option<&str>, character: &str) -> Self {
+        Self {
+            id,
+            kind,
+            skill_name: skill_name.map(Into::into),
+            name: format!("Unknown {}", kind.as_ref()),
+            first_seen: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default(),
+            character: character.into(),
+            selected: false,
+        }
+    }
+
+    /// Converts the unknown buff into buff data for the definitions.
+    pub fn to_buff_data(&self) -> BuffData {
+        BuffData::new(self.id, self.name.clone(), UNKNOWN_BUFF_TEXT)
+    }
+}
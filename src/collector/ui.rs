@@ -0,0 +1,120 @@
+use super::Collector;
+use arc_util::ui::{render, Component, Windowable};
+use arcdps::imgui::{TableColumnSetup, Ui};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+impl Collector {
+    /// Formats the time since a given unix timestamp for display.
+    fn format_age(timestamp: u64) -> String {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+        let secs = now.saturating_sub(timestamp);
+        match secs {
+            0..=59 => format!("{secs}s ago"),
+            60..=3599 => format!("{}m ago", secs / 60),
+            3600..=86399 => format!("{}h ago", secs / 3600),
+            _ => format!("{}d ago", secs / 86400),
+        }
+    }
+}
+
+impl Component<()> for Collector {
+    fn render(&mut self, ui: &Ui, _: ()) {
+        const SPACING: f32 = 5.0;
+
+        if self.entries.is_empty() {
+            ui.text("No unknown buffs collected");
+            return;
+        }
+
+        if let Some(_table) = ui.begin_table_header(
+            "##collector-table",
+            [
+                TableColumnSetup::new(""),
+                TableColumnSetup::new("Kind"),
+                TableColumnSetup::new("Id"),
+                TableColumnSetup::new("Skill"),
+                TableColumnSetup::new("Name"),
+                TableColumnSetup::new("Character"),
+                TableColumnSetup::new("First seen"),
+            ],
+        ) {
+            for entry in &mut self.entries {
+                ui.table_next_row();
+
+                ui.table_next_column();
+                ui.checkbox(format!("##select-{}", entry.id), &mut entry.selected);
+
+                ui.table_next_column();
+                ui.text(entry.kind.as_ref());
+
+                ui.table_next_column();
+                ui.text(entry.id.to_string());
+                render::item_context_menu(format!("##collector-context-{}", entry.id), || {
+                    if ui.small_button("Copy id") {
+                        ui.set_clipboard_text(entry.id.to_string());
+                    }
+                });
+
+                ui.table_next_column();
+                ui.text(entry.skill_name.as_deref().unwrap_or_default());
+
+                ui.table_next_column();
+                ui.set_next_item_width(render::ch_width(ui, 24));
+                ui.input_text(format!("##name-{}", entry.id), &mut entry.name)
+                    .build();
+
+                ui.table_next_column();
+                ui.text(&entry.character);
+
+                ui.table_next_column();
+                ui.text(Self::format_age(entry.first_seen));
+            }
+        }
+
+        // selection buttons
+        if ui.button("Select all") {
+            for entry in &mut self.entries {
+                entry.selected = true;
+            }
+        }
+        ui.same_line_with_spacing(0.0, SPACING);
+        if ui.button("Select none") {
+            for entry in &mut self.entries {
+                entry.selected = false;
+            }
+        }
+
+        // export buttons
+        let has_selected = self.has_selected();
+        ui.same_line_with_spacing(0.0, SPACING);
+        if ui.button("Copy JSON") && has_selected {
+            ui.set_clipboard_text(self.selected_data().to_json_pretty());
+        }
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Copy the selected entries as definitions JSON.");
+        }
+
+        ui.same_line_with_spacing(0.0, SPACING);
+        if ui.button("Save learned") && has_selected {
+            self.learned = Some(self.selected_data());
+        }
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Save the selected entries into the learned definitions file.");
+        }
+
+        ui.same_line_with_spacing(0.0, SPACING);
+        if ui.button("Remove") {
+            self.remove_selected();
+        }
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Remove the selected entries.");
+        }
+    }
+}
+
+impl Windowable<()> for Collector {
+    const CONTEXT_MENU: bool = false;
+}
@@ -1,3 +1,4 @@
+use super::Language;
 use arcdps::Profession;
 
 /// Text shown when buff state is not known (yet).
@@ -18,6 +19,17 @@ pub const DIMINISHED: u32 = 46668;
 /// Reinforced Armor buff id.
 pub const REINFORCED: u32 = 9283;
 
+/// Names of the Nourishment & Enhancement buffs for each client language.
+///
+/// Used to detect unknown food & utility buffs.
+pub const CONSUMABLE_NAMES: &[(Language, &str, &str)] = &[
+    (Language::English, "Nourishment", "Enhancement"),
+    (Language::German, "Ernährung", "Verbesserung"),
+    (Language::French, "Alimentation", "Amélioration"),
+    (Language::Spanish, "Alimento", "Mejora"),
+    (Language::Chinese, "营养", "强化"),
+];
+
 /// Professions sorted alphabetically.
 pub const PROFESSIONS: &[Profession] = &[
     Profession::Unknown,
@@ -0,0 +1,301 @@
+use super::{
+    BuffData, CategoryData, DefData, EntryError, IgnoreData, IgnoreName, LocalizedNames, Rarity,
+    StatModifier,
+};
+use csv::{Position, ReaderBuilder, StringRecord, Trim, WriterBuilder};
+use serde::{Deserialize, Serialize};
+use std::{iter, mem, path::Path};
+
+/// Column headers of the CSV definitions format.
+const HEADERS: [&str; 11] = [
+    "kind", "id", "name", "stats", "display", "rarity", "duration", "item", "aliases", "reason",
+    "category",
+];
+
+/// Separator between multiple values in a single field.
+///
+/// Separators & backslashes within values are escaped with a backslash.
+const SEPARATOR: &str = "; ";
+
+impl DefData {
+    /// Checks whether the path points to a CSV definitions file.
+    pub fn is_csv(path: impl AsRef<Path>) -> bool {
+        path.as_ref()
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"))
+    }
+
+    /// Parses definitions data from CSV while skipping invalid rows.
+    ///
+    /// Returns the data together with errors for all skipped rows.
+    pub fn from_csv(content: &str) -> Result<(Self, Vec<EntryError>), String> {
+        let mut reader = ReaderBuilder::new()
+            .trim(Trim::All)
+            .flexible(true)
+            .from_reader(content.as_bytes());
+
+        let headers = reader.headers().map_err(|err| err.to_string())?.clone();
+        for required in ["kind", "id"] {
+            if !headers.iter().any(|header| header == required) {
+                return Err(format!("missing column \"{required}\""));
+            }
+        }
+
+        let mut data = Self::default();
+        let mut errors = Vec::new();
+        for record in reader.records() {
+            let record = match record {
+                Ok(record) => record,
+                Err(err) => {
+                    errors.push(EntryError::new(
+                        row_path(err.position()),
+                        None,
+                        err.to_string(),
+                    ));
+                    continue;
+                }
+            };
+            let path = row_path(record.position());
+
+            // skip empty rows
+            if record.iter().all(str::is_empty) {
+                continue;
+            }
+
+            let result = record
+                .deserialize::<CsvRow>(Some(&headers))
+                .map_err(|err| err.to_string())
+                .and_then(|row| row.add_to(&mut data));
+            if let Err(err) = result {
+                errors.push(EntryError::new(path, record_id(&headers, &record), err));
+            }
+        }
+
+        Ok((data, errors))
+    }
+
+    /// Formats the definitions data as CSV.
+    ///
+    /// Localized names are not included.
+    pub fn to_csv(&self) -> String {
+        let mut writer = WriterBuilder::new()
+            .has_headers(false)
+            .from_writer(Vec::new());
+
+        let rows = self
+            .food
+            .iter()
+            .map(|food| CsvRow::from_buff("food", food))
+            .chain(
+                self.utility
+                    .iter()
+                    .map(|util| CsvRow::from_buff("utility", util)),
+            )
+            .chain(self.categories.iter().flat_map(|category| {
+                let declaration = CsvRow {
+                    kind: "category".into(),
+                    name: category.name.to_string(),
+                    display: category.display.as_deref().unwrap_or_default().into(),
+                    ..CsvRow::default()
+                };
+                let buffs = category.buffs.iter().map(|buff| CsvRow {
+                    category: category.name.to_string(),
+                    ..CsvRow::from_buff("consumable", buff)
+                });
+                iter::once(declaration).chain(buffs)
+            }))
+            .chain(self.ignore.iter().map(|ignore| CsvRow {
+                kind: "ignore".into(),
+                id: Some(ignore.id),
+                reason: ignore.reason.as_deref().unwrap_or_default().into(),
+                ..CsvRow::default()
+            }))
+            .chain(self.ignore_names.iter().map(|rule| CsvRow {
+                kind: "ignore_name".into(),
+                name: rule.name.to_string(),
+                reason: rule.reason.as_deref().unwrap_or_default().into(),
+                ..CsvRow::default()
+            }));
+
+        writer
+            .write_record(HEADERS)
+            .expect("failed to format definitions");
+        for row in rows {
+            writer.serialize(row).expect("failed to format definitions");
+        }
+
+        let bytes = writer.into_inner().expect("failed to format definitions");
+        String::from_utf8(bytes).expect("invalid utf8 in definitions")
+    }
+}
+
+/// Returns the path of a row at the given position.
+fn row_path(position: Option<&Position>) -> String {
+    match position {
+        Some(pos) => format!("line {}", pos.line()),
+        None => "row".into(),
+    }
+}
+
+/// Attempts to read the buff id of a raw row.
+fn record_id(headers: &StringRecord, record: &StringRecord) -> Option<u32> {
+    let index = headers.iter().position(|header| header == "id")?;
+    record.get(index)?.parse().ok()
+}
+
+/// Row in the CSV definitions format.
+///
+/// Ignore rows use the reason, ignore name rows use name & reason.
+/// Category rows declare a category with name & display,
+/// consumable rows are buffs in the category named in the category column.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct CsvRow {
+    kind: String,
+    id: Option<u32>,
+    name: String,
+    stats: String,
+    display: String,
+    rarity: Option<Rarity>,
+    duration: Option<u32>,
+    item: Option<u32>,
+    aliases: String,
+    reason: String,
+    category: String,
+}
+
+impl CsvRow {
+    /// Creates a new row from buff data.
+    fn from_buff(kind: &str, data: &BuffData) -> Self {
+        Self {
+            kind: kind.into(),
+            id: Some(data.id),
+            name: data.name.to_string(),
+            stats: data
+                .stats
+                .iter()
+                .map(|stat| escape_value(&stat.to_string()))
+                .collect::<Vec<_>>()
+                .join(SEPARATOR),
+            display: data.display.to_string(),
+            rarity: (data.rarity != Rarity::Basic).then_some(data.rarity),
+            duration: data.duration,
+            item: data.item,
+            aliases: data
+                .aliases
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(SEPARATOR),
+            reason: String::new(),
+            category: String::new(),
+        }
+    }
+
+    /// Adds the row to the definitions data.
+    fn add_to(self, data: &mut DefData) -> Result<(), String> {
+        let reason = (!self.reason.is_empty()).then(|| self.reason.clone().into());
+        match self.kind.to_lowercase().as_str() {
+            "food" => data.food.push(self.into_buff()?),
+            "utility" => data.utility.push(self.into_buff()?),
+            "ignore" => data
+                .ignore
+                .push(IgnoreData::new(self.id.ok_or("missing id")?, reason)),
+            "ignore_name" if self.name.is_empty() => return Err("missing name".into()),
+            "ignore_name" => data.ignore_names.push(IgnoreName {
+                name: self.name.into(),
+                reason,
+            }),
+            "category" if self.name.is_empty() => return Err("missing name".into()),
+            "category" => {
+                let display = (!self.display.is_empty()).then(|| self.display.into());
+                let category = category_mut(data, self.name);
+                category.display = display.or(category.display.take());
+            }
+            "consumable" if self.category.is_empty() => return Err("missing category".into()),
+            "consumable" => {
+                let name = self.category.clone();
+                let buff = self.into_buff()?;
+                category_mut(data, name).buffs.push(buff);
+            }
+            _ => return Err(format!("unknown kind \"{}\"", self.kind)),
+        }
+        Ok(())
+    }
+
+    /// Converts the row into buff data.
+    fn into_buff(self) -> Result<BuffData, String> {
+        let id = self.id.ok_or("missing id")?;
+        if self.name.is_empty() {
+            return Err("missing name".into());
+        }
+        let aliases = split_values(&self.aliases)
+            .map(|alias| {
+                alias
+                    .parse::<u32>()
+                    .map_err(|_| format!("invalid alias \"{alias}\""))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(BuffData {
+            id,
+            name: self.name.into(),
+            names: LocalizedNames::default(),
+            stats: split_values(&self.stats)
+                .map(|stat| StatModifier::from(stat.as_str()))
+                .collect::<Vec<_>>()
+                .into(),
+            display: self.display.into(),
+            rarity: self.rarity.unwrap_or_default(),
+            duration: self.duration,
+            item: self.item,
+            aliases: aliases.into(),
+        })
+    }
+}
+
+/// Returns the category with the given name, creating it if missing.
+fn category_mut(data: &mut DefData, name: String) -> &mut CategoryData {
+    let index = match data
+        .categories
+        .iter()
+        .position(|category| category.name == name)
+    {
+        Some(index) => index,
+        None => {
+            data.categories.push(CategoryData {
+                name: name.into(),
+                display: None,
+                buffs: Vec::new(),
+            });
+            data.categories.len() - 1
+        }
+    };
+    &mut data.categories[index]
+}
+
+/// Escapes separators & backslashes in a single value.
+fn escape_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace(';', "\\;")
+}
+
+/// Splits a field into its non-empty unescaped values.
+fn split_values(field: &str) -> impl Iterator<Item = String> {
+    let mut values = Vec::new();
+    let mut current = String::new();
+    let mut chars = field.chars();
+    while let Some(char) = chars.next() {
+        match char {
+            '\\' => current.extend(chars.next()),
+            ';' => values.push(mem::take(&mut current)),
+            _ => current.push(char),
+        }
+    }
+    values.push(current);
+
+    values.into_iter().filter_map(|value| {
+        let value = value.trim();
+        (!value.is_empty()).then(|| value.to_owned())
+    })
+}
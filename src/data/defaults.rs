@@ -0,0 +1,46 @@
+use super::{
+    Attribute, BuffData, Category, DefinitionEntry, DefinitionKind, DefinitionSource, IgnoreName,
+    LocalizedNames, Rarity, StatModifier,
+};
+use std::borrow::Cow;
+
+/// Default definitions generated from `definitions.json` at build time.
+pub static DEFAULTS: DefaultTable = include!(concat!(env!("OUT_DIR"), "/definitions.rs"));
+
+/// Static read-only table of definitions.
+#[derive(Debug)]
+pub struct DefaultTable {
+    /// Definition entries.
+    ///
+    /// Sorted alphabetically for UI usage.
+    pub entries: &'static [DefinitionEntry],
+
+    /// Buff ids & aliases with the position of their entry.
+    ///
+    /// Sorted by id for lookups, ids with their own entry take precedence over aliases.
+    pub index: &'static [(u32, usize)],
+
+    /// Ignore rules matching buff names.
+    pub ignore_names: &'static [IgnoreName],
+
+    /// Consumable categories.
+    pub categories: &'static [Category],
+}
+
+impl DefaultTable {
+    /// Empty table without any definitions.
+    pub const EMPTY: Self = Self {
+        entries: &[],
+        index: &[],
+        ignore_names: &[],
+        categories: &[],
+    };
+
+    /// Returns the entry for the buff with the given id or alias.
+    pub fn get(&self, buff_id: u32) -> Option<&'static DefinitionEntry> {
+        self.index
+            .binary_search_by_key(&buff_id, |(id, _)| *id)
+            .ok()
+            .map(|i| &self.entries[self.index[i].1])
+    }
+}
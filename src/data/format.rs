@@ -0,0 +1,357 @@
+use super::{
+    BuffData, CategoryData, DefData, IgnoreData, IgnoreName, LocalizedNames, Migration, Rarity,
+    DIMINISHED, FORMAT_VERSION, MALNOURISHED,
+};
+use crate::util::{locate_jsonc, JsonError};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+use std::{fmt, iter};
+
+/// Maximum length of display names in the tracker table.
+pub const MAX_DISPLAY_LEN: usize = 6;
+
+impl DefData {
+    /// Converts a JSON value into definitions data while skipping invalid entries.
+    ///
+    /// Data from older format versions is migrated first.
+    /// Returns the data together with errors for all skipped entries.
+    pub fn from_value_lenient(mut value: Value) -> Result<(Self, Vec<EntryError>), JsonError> {
+        Migration::apply(&mut value)?;
+        let Value::Object(mut object) = value else {
+            return Err(JsonError::Data("expected definitions object".into()));
+        };
+
+        let mut errors = Vec::new();
+        let data = Self {
+            food: Self::parse_section(&mut object, "food", &mut errors),
+            utility: Self::parse_section(&mut object, "utility", &mut errors),
+            ignore: Self::parse_section(&mut object, "ignore", &mut errors),
+            ignore_names: Self::parse_section(&mut object, "ignore_names", &mut errors),
+            categories: Self::parse_categories(&mut object, &mut errors),
+        };
+
+        Ok((data, errors))
+    }
+
+    /// Parses a section of entries, skipping invalid entries.
+    fn parse_section<T>(
+        object: &mut Map<String, Value>,
+        key: &str,
+        errors: &mut Vec<EntryError>,
+    ) -> Vec<T>
+    where
+        T: DeserializeOwned,
+    {
+        Self::parse_entries(object.remove(key), key, errors)
+    }
+
+    /// Parses the consumable categories, skipping invalid categories & buff entries.
+    fn parse_categories(
+        object: &mut Map<String, Value>,
+        errors: &mut Vec<EntryError>,
+    ) -> Vec<CategoryData> {
+        let categories: Vec<Value> = Self::parse_section(object, "categories", errors);
+        categories
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, mut category)| {
+                let key = format!("categories[{i}]");
+                let buffs = category
+                    .as_object_mut()
+                    .and_then(|category| category.remove("buffs"));
+                let buffs = Self::parse_entries(buffs, &format!("{key}.buffs"), errors);
+                serde_json::from_value(category)
+                    .map(|category| CategoryData { buffs, ..category })
+                    .map_err(|err| errors.push(EntryError::new(key, None, err.to_string())))
+                    .ok()
+            })
+            .collect()
+    }
+
+    /// Parses an array of entries, skipping invalid entries.
+    fn parse_entries<T>(value: Option<Value>, key: &str, errors: &mut Vec<EntryError>) -> Vec<T>
+    where
+        T: DeserializeOwned,
+    {
+        match value {
+            None | Some(Value::Null) => Vec::new(),
+            Some(Value::Array(entries)) => entries
+                .into_iter()
+                .enumerate()
+                .filter_map(|(i, entry)| {
+                    let id = entry_id(&entry);
+                    serde_json::from_value(entry)
+                        .map_err(|err| {
+                            errors.push(EntryError::new(format!("{key}[{i}]"), id, err.to_string()))
+                        })
+                        .ok()
+                })
+                .collect(),
+            Some(_) => {
+                errors.push(EntryError::new(key, None, "expected array"));
+                Vec::new()
+            }
+        }
+    }
+
+    /// Formats the definitions data as pretty JSON in the definitions file format.
+    pub fn to_json_pretty(&self) -> String {
+        serde_json::to_string_pretty(&FileData::from(self)).expect("failed to format definitions")
+    }
+
+    /// Sorts the entries in the order of the default definitions.
+    pub fn sort(&mut self) {
+        self.food.sort_by(|a, b| a.name.cmp(&b.name));
+        self.utility.sort_by(|a, b| a.name.cmp(&b.name));
+        self.ignore.sort_by_key(|ignore| ignore.id);
+        self.ignore_names.sort_by(|a, b| a.name.cmp(&b.name));
+        self.categories.sort_by(|a, b| a.name.cmp(&b.name));
+        for category in &mut self.categories {
+            category.buffs.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+    }
+
+    /// Checks the definitions data for problems.
+    ///
+    /// The default definitions additionally require the Malnourished & Diminished entries.
+    pub fn lint(&self, defaults: bool) -> Vec<Lint> {
+        let mut lints = Vec::new();
+
+        // check for duplicate ids & aliases across all sections
+        let buffs = self
+            .food
+            .iter()
+            .chain(&self.utility)
+            .chain(self.category_buffs().map(|(_, buff)| buff));
+        let mut ids = buffs
+            .clone()
+            .flat_map(|buff| iter::once(buff.id).chain(buff.aliases.iter().copied()))
+            .chain(self.ignore.iter().map(|ignore| ignore.id))
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+        for window in ids.windows(2) {
+            if let [a, b] = window {
+                if a == b && !lints.contains(&Lint::Duplicate(*a)) {
+                    lints.push(Lint::Duplicate(*a));
+                }
+            }
+        }
+
+        // check display name lengths
+        for buff in buffs {
+            if buff.display.chars().count() > MAX_DISPLAY_LEN {
+                lints.push(Lint::DisplayLength {
+                    id: buff.id,
+                    display: buff.display.to_string(),
+                });
+            }
+        }
+
+        // check for required entries
+        if defaults {
+            if !self.food.iter().any(|food| food.id == MALNOURISHED) {
+                lints.push(Lint::Missing(MALNOURISHED));
+            }
+            if !self.utility.iter().any(|util| util.id == DIMINISHED) {
+                lints.push(Lint::Missing(DIMINISHED));
+            }
+        }
+
+        lints
+    }
+}
+
+/// Attempts to read the buff id of a raw entry.
+fn entry_id(entry: &Value) -> Option<u32> {
+    entry
+        .as_u64()
+        .or_else(|| entry.get("id").and_then(Value::as_u64))
+        .and_then(|id| id.try_into().ok())
+}
+
+/// Definitions data in the definitions file format.
+#[derive(Serialize)]
+struct FileData<'a> {
+    version: u32,
+    food: Vec<FileBuffData<'a>>,
+    utility: Vec<FileBuffData<'a>>,
+    ignore: Vec<FileIgnoreData<'a>>,
+
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    ignore_names: &'a [IgnoreName],
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    categories: Vec<FileCategoryData<'a>>,
+}
+
+impl<'a> From<&'a DefData> for FileData<'a> {
+    fn from(data: &'a DefData) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            food: data.food.iter().map(Into::into).collect(),
+            utility: data.utility.iter().map(Into::into).collect(),
+            ignore: data.ignore.iter().map(Into::into).collect(),
+            ignore_names: &data.ignore_names,
+            categories: data.categories.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Consumable category in the definitions file format.
+#[derive(Serialize)]
+struct FileCategoryData<'a> {
+    name: &'a str,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    display: Option<&'a str>,
+
+    buffs: Vec<FileBuffData<'a>>,
+}
+
+impl<'a> From<&'a CategoryData> for FileCategoryData<'a> {
+    fn from(category: &'a CategoryData) -> Self {
+        Self {
+            name: &category.name,
+            display: category.display.as_deref(),
+            buffs: category.buffs.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Ignore entry in the definitions file format.
+///
+/// Entries without reason are written as bare id.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum FileIgnoreData<'a> {
+    Id(u32),
+    Entry { id: u32, reason: &'a str },
+}
+
+impl<'a> From<&'a IgnoreData> for FileIgnoreData<'a> {
+    fn from(data: &'a IgnoreData) -> Self {
+        match &data.reason {
+            Some(reason) => Self::Entry {
+                id: data.id,
+                reason,
+            },
+            None => Self::Id(data.id),
+        }
+    }
+}
+
+/// Buff data in the definitions file format.
+///
+/// Stats are written as text & unset optional fields are omitted.
+#[derive(Serialize)]
+struct FileBuffData<'a> {
+    id: u32,
+    name: &'a str,
+
+    #[serde(skip_serializing_if = "LocalizedNames::is_empty")]
+    names: &'a LocalizedNames,
+
+    stats: Vec<String>,
+    display: &'a str,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rarity: Option<Rarity>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    item: Option<u32>,
+
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    aliases: &'a [u32],
+}
+
+impl<'a> From<&'a BuffData> for FileBuffData<'a> {
+    fn from(buff: &'a BuffData) -> Self {
+        Self {
+            id: buff.id,
+            name: &buff.name,
+            names: &buff.names,
+            stats: buff.stats.iter().map(ToString::to_string).collect(),
+            display: &buff.display,
+            rarity: Some(buff.rarity).filter(|rarity| *rarity != Rarity::Basic),
+            duration: buff.duration,
+            item: buff.item,
+            aliases: &buff.aliases,
+        }
+    }
+}
+
+/// Error for a single skipped entry in a definitions file.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EntryError {
+    /// Location of the entry in the file, e.g. `food[3]`.
+    pub path: String,
+
+    /// Buff id of the entry, if present.
+    pub id: Option<u32>,
+
+    /// Line & column of the entry in the file, if known.
+    pub location: Option<(usize, usize)>,
+
+    /// Error message.
+    pub message: String,
+}
+
+impl EntryError {
+    /// Creates a new entry error.
+    pub fn new(path: impl Into<String>, id: Option<u32>, message: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            id,
+            location: None,
+            message: message.into(),
+        }
+    }
+
+    /// Looks up the line & column of the entry in the JSONC input it was parsed from.
+    pub fn locate(&mut self, input: &str) {
+        self.location = locate_jsonc(input, &self.path);
+    }
+}
+
+impl fmt::Display for EntryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.path)?;
+        if let Some(id) = self.id {
+            write!(f, " (id {id})")?;
+        }
+        if let Some((line, column)) = self.location {
+            write!(f, " at line {line} column {column}")?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// Problem found when checking definitions data.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Lint {
+    /// Multiple entries use the same id.
+    Duplicate(u32),
+
+    /// Display name exceeds the maximum length.
+    DisplayLength { id: u32, display: String },
+
+    /// Required entry is missing.
+    Missing(u32),
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Duplicate(id) => write!(f, "duplicate entry for id {id}"),
+            Self::DisplayLength { id, display } => write!(
+                f,
+                "long display name \"{display}\" for id {id}: {} characters, max {MAX_DISPLAY_LEN}",
+                display.chars().count()
+            ),
+            Self::Missing(id) => write!(f, "missing entry for id {id}"),
+        }
+    }
+}
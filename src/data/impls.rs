@@ -1,28 +1,478 @@
 use super::*;
+use crate::util::{item_chat_link, parse_jsonc_value};
 use arc_util::colors::{self, Color};
+use std::{borrow::Cow, fs, io, path::Path};
 
 impl DefData {
     /// Returns the default definitions data.
+    ///
+    /// This clones the static default table, see [`DEFAULTS`] for borrowed access.
     pub fn with_defaults() -> Self {
-        include!(concat!(env!("OUT_DIR"), "/definitions.rs"))
+        let mut data = Self {
+            ignore_names: DEFAULTS.ignore_names.to_vec(),
+            categories: DEFAULTS.categories.iter().map(CategoryData::from).collect(),
+            ..Self::default()
+        };
+        for entry in DEFAULTS.entries {
+            data.push_entry(entry);
+        }
+        data
+    }
+
+    /// Adds a clone of a definitions entry.
+    ///
+    /// Buffs in an unknown category create the category without display name.
+    pub fn push_entry(&mut self, entry: &DefinitionEntry) {
+        match &entry.def {
+            DefinitionKind::Food(food) => self.food.push(food.clone()),
+            DefinitionKind::Util(util) => self.utility.push(util.clone()),
+            DefinitionKind::Category(name, data) => {
+                match self
+                    .categories
+                    .iter_mut()
+                    .find(|category| category.name == *name)
+                {
+                    Some(category) => category.buffs.push(data.clone()),
+                    None => self.categories.push(CategoryData {
+                        name: name.clone(),
+                        display: None,
+                        buffs: vec![data.clone()],
+                    }),
+                }
+            }
+            DefinitionKind::Ignore(reason) => {
+                self.ignore.push(IgnoreData::new(entry.id, reason.clone()))
+            }
+        }
     }
 
     /// Returns the total number of definition entries.
     pub fn len(&self) -> usize {
-        self.food.len() + self.utility.len() + self.ignore.len()
+        let categories: usize = self
+            .categories
+            .iter()
+            .map(|category| category.buffs.len())
+            .sum();
+        self.food.len() + self.utility.len() + categories + self.ignore.len()
+    }
+
+    /// Checks whether there are no definition entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 
-    /// Converts this into an iterator over all entries.
-    pub fn into_entries(self) -> impl Iterator<Item = DefinitionEntry> {
+    /// Converts this into an iterator over all entries from the given source.
+    pub fn into_entries(self, source: DefinitionSource) -> impl Iterator<Item = DefinitionEntry> {
         self.food
             .into_iter()
-            .map(DefinitionEntry::new_food)
-            .chain(self.utility.into_iter().map(DefinitionEntry::new_util))
-            .chain(self.ignore.into_iter().map(DefinitionEntry::new_ignore))
+            .map(move |data| DefinitionEntry::new_food(data, source))
+            .chain(
+                self.utility
+                    .into_iter()
+                    .map(move |data| DefinitionEntry::new_util(data, source)),
+            )
+            .chain(self.categories.into_iter().flat_map(move |category| {
+                let name = category.name;
+                category
+                    .buffs
+                    .into_iter()
+                    .map(move |data| DefinitionEntry::new_category(name.clone(), data, source))
+            }))
+            .chain(
+                self.ignore
+                    .into_iter()
+                    .map(move |data| DefinitionEntry::new_ignore(data, source)),
+            )
+    }
+
+    /// Finds entries overriding the given default definitions without need.
+    ///
+    /// Overrides are stale if identical to the default definition.
+    pub fn stale_overrides(&self, defaults: &DefaultTable) -> Vec<StaleOverride> {
+        // aliases of default entries are no overrides
+        let default = |id: u32| {
+            defaults
+                .get(id)
+                .filter(|entry| entry.id == id)
+                .map(|entry| &entry.def)
+        };
+
+        let mut stale = Vec::new();
+        let mut check = |id: u32, name: &str, identical: Option<bool>| {
+            if identical == Some(true) {
+                stale.push(StaleOverride {
+                    id,
+                    name: name.into(),
+                    reason: StaleReason::Identical,
+                });
+            }
+        };
+
+        for food in &self.food {
+            let default = default(food.id);
+            check(
+                food.id,
+                &food.name,
+                default.map(|def| matches!(def, DefinitionKind::Food(data) if data == food)),
+            );
+        }
+        for util in &self.utility {
+            let default = default(util.id);
+            check(
+                util.id,
+                &util.name,
+                default.map(|def| matches!(def, DefinitionKind::Util(data) if data == util)),
+            );
+        }
+        for (category, buff) in self.category_buffs() {
+            let default = default(buff.id);
+            check(
+                buff.id,
+                &buff.name,
+                default.map(|def| {
+                    matches!(def, DefinitionKind::Category(name, data) if name == category && data == buff)
+                }),
+            );
+        }
+        for ignore in &self.ignore {
+            let default = default(ignore.id);
+            check(
+                ignore.id,
+                "",
+                default.map(
+                    |def| matches!(def, DefinitionKind::Ignore(reason) if *reason == ignore.reason),
+                ),
+            );
+        }
+
+        stale
+    }
+
+    /// Loads definitions data from a given file while skipping invalid entries.
+    ///
+    /// Files with a `.csv` extension are read as CSV, all others as JSON.
+    /// Returns the data together with errors for all skipped entries.
+    pub fn load_file(path: impl AsRef<Path>) -> Result<(Self, Vec<EntryError>), LoadError> {
+        let path = path.as_ref();
+        if Self::is_csv(path) {
+            let content = read_file(path)?;
+            Self::from_csv(&content).map_err(LoadError::InvalidCSV)
+        } else {
+            Self::load_file_migrated(path).map(|(data, errors, _)| (data, errors))
+        }
+    }
+
+    /// Loads definitions data from a given file while skipping invalid entries.
+    ///
+    /// Returns the data together with errors for all skipped entries
+    /// and the migration to the current format version.
+    pub fn load_file_migrated(
+        path: impl AsRef<Path>,
+    ) -> Result<(Self, Vec<EntryError>, Migration), LoadError> {
+        let content = read_file(path)?;
+
+        // migrate & parse valid data
+        let mut value = parse_jsonc_value(&content).map_err(LoadError::InvalidJSON)?;
+        let migration = Migration::apply(&mut value).map_err(LoadError::InvalidJSON)?;
+        let (data, mut errors) = Self::from_value_lenient(value).map_err(LoadError::InvalidJSON)?;
+        for err in &mut errors {
+            err.locate(&content);
+        }
+
+        Ok((data, errors, migration))
+    }
+
+    /// Merges other definitions data into this, replacing entries with the same id.
+    pub fn merge(&mut self, other: Self) {
+        for food in other.food {
+            self.remove(food.id);
+            self.food.push(food);
+        }
+        for util in other.utility {
+            self.remove(util.id);
+            self.utility.push(util);
+        }
+        for ignore in other.ignore {
+            self.remove(ignore.id);
+            self.ignore.push(ignore);
+        }
+        for rule in other.ignore_names {
+            self.ignore_names.retain(|old| old.name != rule.name);
+            self.ignore_names.push(rule);
+        }
+        for category in other.categories {
+            for buff in &category.buffs {
+                self.remove(buff.id);
+            }
+            match self
+                .categories
+                .iter_mut()
+                .find(|old| old.name == category.name)
+            {
+                Some(old) => {
+                    if category.display.is_some() {
+                        old.display = category.display;
+                    }
+                    old.buffs.extend(category.buffs);
+                }
+                None => self.categories.push(category),
+            }
+        }
+    }
+
+    /// Removes all entries with the given id.
+    pub fn remove(&mut self, id: u32) {
+        self.food.retain(|food| food.id != id);
+        self.utility.retain(|util| util.id != id);
+        for category in &mut self.categories {
+            category.buffs.retain(|buff| buff.id != id);
+        }
+        self.ignore.retain(|ignore| ignore.id != id);
+    }
+}
+
+impl CategoryData {
+    /// Returns the category declaration without buffs.
+    pub fn to_category(&self) -> Category {
+        Category {
+            name: self.name.clone(),
+            display: self.display.clone(),
+        }
+    }
+}
+
+impl From<&Category> for CategoryData {
+    fn from(category: &Category) -> Self {
+        Self {
+            name: category.name.clone(),
+            display: category.display.clone(),
+            buffs: Vec::new(),
+        }
+    }
+}
+
+impl Category {
+    /// Returns the display name in buff tracker table.
+    ///
+    /// Falls back to the name.
+    pub fn display_name(&self) -> &str {
+        self.display.as_deref().unwrap_or(&self.name)
+    }
+}
+
+impl IgnoreData {
+    /// Creates a new ignore entry.
+    pub fn new(id: u32, reason: Option<Cow<'static, str>>) -> Self {
+        Self { id, reason }
+    }
+}
+
+impl From<u32> for IgnoreData {
+    fn from(id: u32) -> Self {
+        Self::new(id, None)
+    }
+}
+
+impl IgnoreName {
+    /// Checks whether the given buff name matches this rule.
+    ///
+    /// A `*` in the rule matches any sequence of characters.
+    pub fn matches(&self, name: &str) -> bool {
+        let mut parts = self.name.split('*');
+        let first = parts.next().unwrap_or_default();
+        let Some(mut rest) = name.strip_prefix(first) else {
+            return false;
+        };
+
+        match parts.next_back() {
+            // no wildcard
+            None => rest.is_empty(),
+            Some(last) => {
+                // match inner parts in order, leaving the last as suffix
+                for part in parts {
+                    match rest.find(part) {
+                        Some(i) => rest = &rest[i + part.len()..],
+                        None => return false,
+                    }
+                }
+                rest.ends_with(last)
+            }
+        }
+    }
+}
+
+/// Reads the contents of a definitions file.
+fn read_file(path: impl AsRef<Path>) -> Result<String, LoadError> {
+    fs::read_to_string(path).map_err(|err| match err.kind() {
+        io::ErrorKind::NotFound => LoadError::NotFound,
+        _ => LoadError::FailedToRead,
+    })
+}
+
+impl LocalizedNames {
+    /// Returns the name for the given language code.
+    pub fn get(&self, code: &str) -> Option<&str> {
+        self.iter()
+            .find_map(|(known, name)| (known == code).then_some(name))
+    }
+
+    /// Sets the name for the given language code.
+    ///
+    /// Names are kept sorted by language code.
+    pub fn insert(
+        &mut self,
+        code: impl Into<Cow<'static, str>>,
+        name: impl Into<Cow<'static, str>>,
+    ) {
+        let code = code.into();
+        let names = self.0.to_mut();
+        match names.binary_search_by(|(known, _)| known.cmp(&code)) {
+            Ok(i) => names[i].1 = name.into(),
+            Err(i) => names.insert(i, (code, name.into())),
+        }
+    }
+}
+
+impl BuffData {
+    /// Creates new buff data without stats.
+    pub fn new(
+        id: u32,
+        name: impl Into<Cow<'static, str>>,
+        display: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        Self {
+            id,
+            name: name.into(),
+            names: LocalizedNames::default(),
+            stats: Cow::Borrowed(&[]),
+            display: display.into(),
+            rarity: Rarity::Basic,
+            duration: None,
+            item: None,
+            aliases: Cow::Borrowed(&[]),
+        }
+    }
+
+    /// Returns the name in the given language.
+    ///
+    /// Falls back to the English name.
+    pub fn name_in(&self, language: Language) -> &str {
+        self.names.get(language.code()).unwrap_or(&self.name)
+    }
+
+    /// Returns the duration of the buff in milliseconds, if known.
+    pub fn duration_ms(&self) -> Option<u64> {
+        self.duration.map(|minutes| u64::from(minutes) * 60 * 1000)
+    }
+
+    /// Returns the ingame chat link for the item applying the buff, if known.
+    pub fn chat_link(&self) -> Option<String> {
+        self.item.map(|item| item_chat_link(item, 1))
+    }
+
+    /// Returns the wiki search URL in the given language.
+    ///
+    /// Searches by item chat link if the item is known, otherwise by name.
+    /// Falls back to the English wiki for languages without a wiki.
+    pub fn wiki_url(&self, language: Language) -> String {
+        let (wiki, name) = match language.wiki() {
+            Some(wiki) => (wiki, self.name_in(language)),
+            None => (
+                Language::English.wiki().unwrap_or_default(),
+                self.name.as_ref(),
+            ),
+        };
+        match self.chat_link() {
+            Some(link) => {
+                // escape reserved characters of the chat link
+                let link: String = link
+                    .chars()
+                    .map(|c| match c {
+                        '[' => "%5B".into(),
+                        '&' => "%26".into(),
+                        ']' => "%5D".into(),
+                        '+' => "%2B".into(),
+                        '/' => "%2F".into(),
+                        '=' => "%3D".into(),
+                        c => c.to_string(),
+                    })
+                    .collect();
+                format!("{wiki}/wiki/Special:Search/{link}")
+            }
+            None => format!("{wiki}/wiki/Special:Search/{name}"),
+        }
+    }
+}
+
+impl Language {
+    /// All client languages.
+    pub const ALL: [Self; 5] = [
+        Self::English,
+        Self::German,
+        Self::French,
+        Self::Spanish,
+        Self::Chinese,
+    ];
+
+    /// Returns the language code.
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Self::English => "en",
+            Self::German => "de",
+            Self::French => "fr",
+            Self::Spanish => "es",
+            Self::Chinese => "zh",
+        }
+    }
+
+    /// Returns the URL of the official wiki in the language, if present.
+    pub const fn wiki(&self) -> Option<&'static str> {
+        match self {
+            Self::English => Some("https://wiki-en.guildwars2.com"),
+            Self::German => Some("https://wiki-de.guildwars2.com"),
+            Self::French => Some("https://wiki-fr.guildwars2.com"),
+            Self::Spanish => Some("https://wiki-es.guildwars2.com"),
+            Self::Chinese => None,
+        }
+    }
+}
+
+impl From<arcdps::Language> for Language {
+    fn from(language: arcdps::Language) -> Self {
+        match language {
+            arcdps::Language::English => Self::English,
+            arcdps::Language::German => Self::German,
+            arcdps::Language::French => Self::French,
+            arcdps::Language::Spanish => Self::Spanish,
+            arcdps::Language::Chinese => Self::Chinese,
+        }
+    }
+}
+
+impl AsRef<str> for Language {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::English => "English",
+            Self::German => "German",
+            Self::French => "French",
+            Self::Spanish => "Spanish",
+            Self::Chinese => "Chinese",
+        }
     }
 }
 
 impl Rarity {
+    /// All rarities in ascending order.
+    pub const ALL: [Self; 7] = [
+        Self::Basic,
+        Self::Fine,
+        Self::Masterwork,
+        Self::Rare,
+        Self::Exotic,
+        Self::Ascended,
+        Self::Legendary,
+    ];
+
     /// Returns the color associated with the [`Rarity`].
     pub fn color(&self) -> Option<Color> {
         match self {
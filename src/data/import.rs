@@ -0,0 +1,342 @@
+use super::{BuffData, DefData, Rarity, StatModifier};
+use crate::util::{parse_jsonc_value, JsonError};
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Kind of consumable item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ConsumableKind {
+    Food,
+    Utility,
+}
+
+/// Food or utility consumable from a GW2 API `/v2/items` dump.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiConsumable {
+    /// Ingame id of the item.
+    pub item: u32,
+
+    /// Kind of consumable.
+    pub kind: ConsumableKind,
+
+    /// Name of the item.
+    pub name: String,
+
+    /// Rarity of the item.
+    pub rarity: Rarity,
+
+    /// Duration of the applied buff in minutes, if known.
+    pub duration: Option<u32>,
+
+    /// Stats parsed from the item description.
+    pub stats: Vec<StatModifier>,
+}
+
+impl ApiConsumable {
+    /// Parses all food & utility consumables from a `/v2/items` dump.
+    ///
+    /// The dump is an array of items or a single item.
+    /// Other items & items not matching the API format are skipped.
+    pub fn parse_dump(content: &str) -> Result<Vec<Self>, JsonError> {
+        let items = match parse_jsonc_value(content)? {
+            Value::Array(items) => items,
+            item @ Value::Object(_) => vec![item],
+            _ => return Err(JsonError::Data("expected array of items".into())),
+        };
+
+        Ok(items
+            .into_iter()
+            .filter_map(|item| serde_json::from_value(item).ok())
+            .filter_map(Self::from_item)
+            .collect())
+    }
+
+    /// Converts an API item into a consumable, if it is food or utility.
+    fn from_item(item: ApiItem) -> Option<Self> {
+        let details = item.details.filter(|_| item.kind == "Consumable")?;
+        let kind = match details.kind.as_str() {
+            "Food" => ConsumableKind::Food,
+            "Utility" => ConsumableKind::Utility,
+            _ => return None,
+        };
+
+        Some(Self {
+            item: item.id,
+            kind,
+            name: item.name.trim().into(),
+            rarity: parse_rarity(&item.rarity),
+            duration: details
+                .duration_ms
+                .and_then(|ms| u32::try_from(ms / 60_000).ok())
+                .filter(|mins| *mins > 0),
+            stats: details
+                .description
+                .as_deref()
+                .map(parse_stats)
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Applies the item data to existing buff data.
+    ///
+    /// Keeps id, display, localized names & aliases.
+    /// Duration & stats are kept if missing from the item.
+    fn apply(&self, base: BuffData) -> BuffData {
+        BuffData {
+            name: self.name.clone().into(),
+            stats: if self.stats.is_empty() {
+                base.stats
+            } else {
+                self.stats.clone().into()
+            },
+            rarity: self.rarity,
+            duration: self.duration.or(base.duration),
+            item: Some(self.item),
+            ..base
+        }
+    }
+}
+
+/// Result of importing consumables into definitions.
+#[derive(Debug, Default, Clone)]
+pub struct ImportReport {
+    /// Buff ids of added entries.
+    pub added: Vec<u32>,
+
+    /// Buff ids of updated entries.
+    pub updated: Vec<u32>,
+
+    /// Number of consumables matching entries without changes.
+    pub unchanged: usize,
+
+    /// Consumables without known buff id.
+    pub unmatched: Vec<ApiConsumable>,
+}
+
+impl DefData {
+    /// Imports consumables into the definitions data.
+    ///
+    /// Buff ids are looked up by item id or by name in this data first and the known definitions second.
+    /// Matching entries are updated in place, entries only found in the known definitions are added
+    /// as food or utility.
+    /// Ignored buff ids are left untouched.
+    pub fn import(
+        &mut self,
+        consumables: impl IntoIterator<Item = ApiConsumable>,
+        known: &DefData,
+    ) -> ImportReport {
+        let mut report = ImportReport::default();
+
+        for consumable in consumables {
+            let base = self
+                .find_consumable(&consumable)
+                .or_else(|| known.find_consumable(&consumable))
+                .cloned();
+            let Some(base) = base else {
+                report.unmatched.push(consumable);
+                continue;
+            };
+
+            let id = base.id;
+            if self.ignore.iter().any(|ignore| ignore.id == id) {
+                report.unchanged += 1;
+                continue;
+            }
+
+            let data = consumable.apply(base);
+            let categories = self
+                .categories
+                .iter_mut()
+                .flat_map(|category| category.buffs.iter_mut());
+            match self
+                .food
+                .iter_mut()
+                .chain(self.utility.iter_mut())
+                .chain(categories)
+                .find(|buff| buff.id == id)
+            {
+                Some(old) if *old == data => report.unchanged += 1,
+                Some(old) => {
+                    *old = data;
+                    report.updated.push(id);
+                }
+                None => {
+                    match consumable.kind {
+                        ConsumableKind::Food => self.food.push(data),
+                        ConsumableKind::Utility => self.utility.push(data),
+                    }
+                    report.added.push(id);
+                }
+            }
+        }
+
+        report
+    }
+
+    /// Finds the buff data for a consumable.
+    ///
+    /// Matches by item id, or by name for entries without item id.
+    /// Buffs in consumable categories are included.
+    fn find_consumable(&self, consumable: &ApiConsumable) -> Option<&BuffData> {
+        let buffs = self
+            .food
+            .iter()
+            .chain(&self.utility)
+            .chain(self.category_buffs().map(|(_, buff)| buff));
+        buffs
+            .clone()
+            .find(|buff| buff.item == Some(consumable.item))
+            .or_else(|| {
+                buffs
+                    .filter(|buff| buff.item.is_none())
+                    .find(|buff| buff.name == consumable.name)
+            })
+    }
+}
+
+/// Item in the GW2 API format.
+#[derive(Debug, Deserialize)]
+struct ApiItem {
+    id: u32,
+    name: String,
+
+    #[serde(rename = "type")]
+    kind: String,
+
+    #[serde(default)]
+    rarity: String,
+
+    #[serde(default)]
+    details: Option<ApiDetails>,
+}
+
+/// Consumable details in the GW2 API format.
+#[derive(Debug, Deserialize)]
+struct ApiDetails {
+    #[serde(rename = "type")]
+    kind: String,
+
+    #[serde(default)]
+    duration_ms: Option<u64>,
+
+    #[serde(default)]
+    description: Option<String>,
+}
+
+/// Parses an API rarity, unknown rarities are treated as basic.
+fn parse_rarity(rarity: &str) -> Rarity {
+    match rarity {
+        "Fine" => Rarity::Fine,
+        "Masterwork" => Rarity::Masterwork,
+        "Rare" => Rarity::Rare,
+        "Exotic" => Rarity::Exotic,
+        "Ascended" => Rarity::Ascended,
+        "Legendary" => Rarity::Legendary,
+        _ => Rarity::Basic,
+    }
+}
+
+/// Parses stats from an item description, one per line.
+///
+/// Color markup like `<c=@reminder>` is removed, `<br>` tags are treated as line breaks.
+fn parse_stats(description: &str) -> Vec<StatModifier> {
+    let mut text = String::with_capacity(description.len());
+    let mut rest = description;
+    while let Some((before, after)) = rest.split_once('<') {
+        text.push_str(before);
+        let (tag, after) = after.split_once('>').unwrap_or((after, ""));
+        if tag.trim_end_matches('/').trim() == "br" {
+            text.push('\n');
+        }
+        rest = after;
+    }
+    text.push_str(rest);
+
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(StatModifier::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_food(id: u32, name: &'static str) -> BuffData {
+        BuffData {
+            id,
+            name: name.into(),
+            names: Default::default(),
+            stats: Default::default(),
+            display: "Test".into(),
+            rarity: Rarity::Basic,
+            duration: None,
+            item: None,
+            aliases: Default::default(),
+        }
+    }
+
+    #[test]
+    fn item_import() {
+        let dump = r#"[
+            {
+                "id": 91805,
+                "name": "Cilantro Lime Sous-Vide Steak",
+                "type": "Consumable",
+                "rarity": "Ascended",
+                "details": {
+                    "type": "Food",
+                    "duration_ms": 3600000,
+                    "description": "<c=@reminder>66% Life Steal Chance</c>\n+100 Power<br>+70 Ferocity"
+                }
+            },
+            {
+                "id": 2,
+                "name": "Unknown Oil",
+                "type": "Consumable",
+                "rarity": "Fine",
+                "details": { "type": "Utility", "duration_ms": 1800000 }
+            },
+            { "id": 3, "name": "Food", "type": "Consumable", "rarity": "Basic", "details": { "type": "Food", "description": "+10 Power" } },
+            { "id": 4, "name": "Sword", "type": "Weapon", "rarity": "Exotic" },
+            { "id": 5, "name": "Booze", "type": "Consumable", "details": { "type": "Booze" } }
+        ]"#;
+        let items = ApiConsumable::parse_dump(dump).unwrap();
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0].kind, ConsumableKind::Food);
+        assert_eq!(items[0].rarity, Rarity::Ascended);
+        assert_eq!(items[0].duration, Some(60));
+        let stats = items[0].stats.iter().map(ToString::to_string);
+        assert_eq!(
+            stats.collect::<Vec<_>>(),
+            ["66% Life Steal Chance", "+100 Power", "+70 Ferocity"]
+        );
+
+        // buff ids from known definitions, matched by item id or name
+        let mut known = test_food(57244, "Old Name");
+        known.item = Some(91805);
+        let known = DefData {
+            food: vec![known],
+            ..Default::default()
+        };
+        let mut data = DefData {
+            food: vec![test_food(1, "Food")],
+            ..Default::default()
+        };
+        let report = data.import(items.clone(), &known);
+        assert_eq!(report.added, [57244]);
+        assert_eq!(report.updated, [1]);
+        assert_eq!(report.unmatched.len(), 1);
+        assert_eq!(data.food.len(), 2);
+        assert_eq!(data.food[0].item, Some(3));
+        assert_eq!(data.food[1].name, "Cilantro Lime Sous-Vide Steak");
+        assert_eq!(data.food[1].display, "Test");
+
+        // importing again does not duplicate ids
+        let report = data.import(items, &known);
+        assert!(report.added.is_empty() && report.updated.is_empty());
+        assert_eq!(report.unchanged, 2);
+        assert_eq!(data.lint(false), []);
+    }
+}
@@ -0,0 +1,201 @@
+use super::{Rarity, MAX_DISPLAY_LEN};
+use crate::util::JsonError;
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// Current version of the definitions file format.
+///
+/// Files without version are treated as version 0.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Migration step upgrading definitions from the previous version.
+type Step = fn(&mut Map<String, Value>, &mut Vec<String>);
+
+/// Migration steps, the step at index `n` upgrades from version `n` to `n + 1`.
+const STEPS: [Step; FORMAT_VERSION as usize] = [upgrade_v0];
+
+/// Migration of definitions data to the current format version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Migration {
+    /// Version the data was written with.
+    pub from: u32,
+
+    /// Descriptions of the changes made.
+    pub changes: Vec<String>,
+}
+
+impl Migration {
+    /// Migrates a raw definitions object to the current format version.
+    ///
+    /// Data from a newer version is left untouched.
+    pub fn apply(value: &mut Value) -> Result<Self, JsonError> {
+        let Value::Object(object) = value else {
+            return Err(JsonError::Data("expected definitions object".into()));
+        };
+
+        let from = match object.get("version") {
+            None | Some(Value::Null) => 0,
+            Some(version) => version
+                .as_u64()
+                .and_then(|version| version.try_into().ok())
+                .ok_or_else(|| JsonError::Data(format!("invalid version {version}")))?,
+        };
+
+        let mut changes = Vec::new();
+        if from < FORMAT_VERSION {
+            for step in &STEPS[from as usize..] {
+                step(object, &mut changes);
+            }
+            object.insert("version".into(), FORMAT_VERSION.into());
+        }
+
+        Ok(Self { from, changes })
+    }
+
+    /// Returns whether the data was upgraded from an older version.
+    pub fn upgraded(&self) -> bool {
+        self.from < FORMAT_VERSION
+    }
+
+    /// Returns whether the data was written by a newer version.
+    pub fn is_newer(&self) -> bool {
+        self.from > FORMAT_VERSION
+    }
+}
+
+impl fmt::Display for Migration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_newer() {
+            write!(
+                f,
+                "written by newer format version {}, supported up to {FORMAT_VERSION}",
+                self.from
+            )
+        } else {
+            write!(
+                f,
+                "upgraded from format version {} to {FORMAT_VERSION}",
+                self.from
+            )?;
+            for change in &self.changes {
+                write!(f, "\n  {change}")?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Version 0 to 1: normalizes entries which were previously skipped as invalid.
+///
+/// Long display names are reported as lint instead of being shortened.
+fn upgrade_v0(object: &mut Map<String, Value>, changes: &mut Vec<String>) {
+    rename_util(object, changes);
+    for (path, entry) in buff_entries(object) {
+        stats_list(&path, entry, changes);
+        rarity_name(&path, entry, changes);
+        missing_display(&path, entry, changes);
+    }
+}
+
+/// Returns all buff entry objects together with their path.
+fn buff_entries(object: &mut Map<String, Value>) -> Vec<(String, &mut Map<String, Value>)> {
+    let mut entries = Vec::new();
+    for (key, value) in object.iter_mut() {
+        let Value::Array(values) = value else {
+            continue;
+        };
+        match key.as_str() {
+            "food" | "utility" => {
+                for (i, entry) in values.iter_mut().enumerate() {
+                    if let Value::Object(entry) = entry {
+                        entries.push((format!("{key}[{i}]"), entry));
+                    }
+                }
+            }
+            "categories" => {
+                for (i, category) in values.iter_mut().enumerate() {
+                    let Some(Value::Array(buffs)) = category.get_mut("buffs") else {
+                        continue;
+                    };
+                    for (j, entry) in buffs.iter_mut().enumerate() {
+                        if let Value::Object(entry) = entry {
+                            entries.push((format!("categories[{i}].buffs[{j}]"), entry));
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    entries
+}
+
+/// Moves utility entries from the `util` key to `utility`.
+fn rename_util(object: &mut Map<String, Value>, changes: &mut Vec<String>) {
+    let Some(Value::Array(mut util)) = object.remove("util") else {
+        return;
+    };
+    changes.push(format!("util: moved {} entries to utility", util.len()));
+    match object.get_mut("utility") {
+        Some(Value::Array(utility)) => utility.append(&mut util),
+        _ => {
+            object.insert("utility".into(), util.into());
+        }
+    }
+}
+
+/// Converts stats given as single text into a list with one stat per line.
+fn stats_list(path: &str, entry: &mut Map<String, Value>, changes: &mut Vec<String>) {
+    match entry.get("stats") {
+        Some(Value::String(text)) => {
+            let stats = text
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(|line| Value::String(line.into()))
+                .collect::<Vec<_>>();
+            changes.push(format!("{path}: converted stats text to list"));
+            entry.insert("stats".into(), stats.into());
+        }
+        Some(Value::Null) => {
+            entry.remove("stats");
+        }
+        _ => {}
+    }
+}
+
+/// Corrects the case of rarity names.
+fn rarity_name(path: &str, entry: &mut Map<String, Value>, changes: &mut Vec<String>) {
+    let Some(Value::String(name)) = entry.get_mut("rarity") else {
+        return;
+    };
+    let correct = Rarity::ALL
+        .iter()
+        .map(|rarity| format!("{rarity:?}"))
+        .find(|correct| correct.as_str() != name.as_str() && correct.eq_ignore_ascii_case(name));
+    if let Some(correct) = correct {
+        changes.push(format!(
+            "{path}: renamed rarity \"{name}\" to \"{correct}\""
+        ));
+        *name = correct;
+    }
+}
+
+/// Adds display names derived from the name to entries without display name.
+fn missing_display(path: &str, entry: &mut Map<String, Value>, changes: &mut Vec<String>) {
+    if entry.contains_key("display") {
+        return;
+    }
+    let Some(Value::String(name)) = entry.get("name") else {
+        return;
+    };
+    let display = name
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .take(MAX_DISPLAY_LEN)
+        .collect::<String>();
+    changes.push(format!("{path}: added display \"{display}\" from name"));
+    entry.insert("display".into(), display.into());
+}
@@ -1,110 +1,398 @@
 mod constants;
+mod csv_format;
+mod defaults;
+mod format;
 mod impls;
+mod import;
+mod migrate;
+mod pack;
+mod skill_names;
+mod stats;
 mod structs;
 
-use crate::util::parse_jsonc;
-use std::{fs, io, path::Path};
+use crate::util::JsonError;
+use std::{
+    borrow::Cow,
+    collections::{hash_map, HashMap},
+    fmt, iter, mem,
+    path::Path,
+};
 
 pub use self::constants::*;
+pub use self::defaults::*;
+pub use self::format::*;
+pub use self::import::*;
+pub use self::migrate::*;
+pub use self::pack::*;
+pub use self::skill_names::*;
+pub use self::stats::*;
 pub use self::structs::*;
 
 /// Shared buff definitions data.
 #[derive(Debug)]
 pub struct Definitions {
-    /// Buff definitions data.
+    /// Default definitions.
+    ///
+    /// Borrowed from the static table generated at build time.
+    defaults: &'static DefaultTable,
+
+    /// Custom buff definitions data.
     ///
     /// Sorted alphabetically for UI usage.
+    /// Entries with the id of a default entry override it.
     data: Vec<DefinitionEntry>,
+
+    /// Index of buff id to position in the custom data.
+    ///
+    /// Kept in sync with the sorted data for lookups on the event path.
+    index: HashMap<u32, usize>,
+
+    /// Custom ignore rules matching buff names.
+    ///
+    /// Rules with the name of a default rule override it.
+    ignore_names: Vec<IgnoreName>,
+
+    /// Custom consumable categories.
+    ///
+    /// Categories with the name of a default category override it.
+    categories: Vec<Category>,
+
+    /// Current client language.
+    ///
+    /// [`None`] if the language is unknown.
+    language: Option<Language>,
 }
 
 impl Definitions {
     /// Creates a new empty set of definitions.
-    pub const fn empty() -> Self {
-        Self { data: Vec::new() }
+    pub fn empty() -> Self {
+        Self::with_table(&DefaultTable::EMPTY)
     }
 
     /// Creates a new set of definitions with the default definitions.
+    ///
+    /// Default entries are borrowed from the static table and not cloned.
     pub fn with_defaults() -> Self {
-        let mut defs = Self::empty();
+        Self::with_table(&DEFAULTS)
+    }
+
+    /// Creates a new set of definitions on top of the given table.
+    fn with_table(defaults: &'static DefaultTable) -> Self {
+        Self {
+            defaults,
+            data: Vec::new(),
+            index: HashMap::new(),
+            ignore_names: Vec::new(),
+            categories: Vec::new(),
+            language: None,
+        }
+    }
+
+    /// Returns the current client language, if known.
+    pub fn language(&self) -> Option<Language> {
+        self.language
+    }
 
-        // add default defs data
-        defs.add_data(DefData::with_defaults());
+    /// Returns the language used to display names.
+    pub fn display_language(&self) -> Language {
+        self.language.unwrap_or_default()
+    }
+
+    /// Sets the current client language.
+    pub fn set_language(&mut self, language: Option<Language>) {
+        self.language = language;
+    }
+
+    /// Returns the total number of definition entries.
+    pub fn len(&self) -> usize {
+        let overridden = self
+            .data
+            .iter()
+            .filter(|entry| {
+                self.defaults
+                    .get(entry.id)
+                    .is_some_and(|def| def.id == entry.id)
+            })
+            .count();
+        self.defaults.entries.len() - overridden + self.data.len()
+    }
 
-        defs
+    /// Checks whether there are no definition entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 
-    /// Updates an old buff entry or inserts iut as a new entry.
+    /// Checks whether the default entry with the given id is overridden by a custom entry.
+    fn is_overridden(&self, id: u32) -> bool {
+        self.index.get(&id).is_some_and(|&i| self.data[i].id == id)
+    }
+
+    /// Returns the entry for the buff with the given id or alias.
+    ///
+    /// Ids with their own entry take precedence over aliases.
+    fn entry(&self, buff_id: u32) -> Option<&DefinitionEntry> {
+        let custom = self.index.get(&buff_id).map(|&i| &self.data[i]);
+        let default = self
+            .defaults
+            .get(buff_id)
+            .filter(|entry| !self.is_overridden(entry.id));
+        match (custom, default) {
+            (Some(custom), Some(default)) if custom.id != buff_id && default.id == buff_id => {
+                Some(default)
+            }
+            (custom, default) => custom.or(default),
+        }
+    }
+
+    /// Updates an old buff entry or inserts it as a new entry.
     fn update_or_insert(&mut self, new: DefinitionEntry) {
-        if let Some(old) = self.data.iter_mut().find(|entry| entry.id == new.id) {
-            *old = new;
-        } else {
-            self.data.push(new);
+        match self.index.entry(new.id) {
+            // only replace the entry itself, not an entry using the id as alias
+            hash_map::Entry::Occupied(occupied) if self.data[*occupied.get()].id == new.id => {
+                self.data[*occupied.get()] = new
+            }
+            hash_map::Entry::Occupied(mut occupied) => {
+                occupied.insert(self.data.len());
+                self.data.push(new);
+            }
+            hash_map::Entry::Vacant(vacant) => {
+                vacant.insert(self.data.len());
+                self.data.push(new);
+            }
+        }
+    }
+
+    /// Rebuilds the id index from the current data.
+    ///
+    /// Ids with their own entry take precedence over aliases.
+    fn refresh_index(&mut self) {
+        self.index.clear();
+        self.index
+            .extend(self.data.iter().enumerate().map(|(i, entry)| (entry.id, i)));
+        for (i, entry) in self.data.iter().enumerate() {
+            for alias in entry.def.aliases() {
+                self.index.entry(*alias).or_insert(i);
+            }
         }
     }
 
     /// Add definitions from a [`DefData`] collection.
-    pub fn add_data(&mut self, data: DefData) {
+    pub fn add_data(&mut self, mut data: DefData, source: DefinitionSource) {
         // reserve for initial load
         if self.data.is_empty() {
             self.data.reserve(data.len());
+            self.index.reserve(data.len());
+        }
+
+        // add name rules, replacing rules with the same name
+        for rule in mem::take(&mut data.ignore_names) {
+            match self
+                .ignore_names
+                .iter_mut()
+                .find(|old| old.name == rule.name)
+            {
+                Some(old) => *old = rule,
+                None => self.ignore_names.push(rule),
+            }
+        }
+
+        // add categories, only replacing the display name if set
+        for category in &data.categories {
+            let custom = self
+                .categories
+                .iter_mut()
+                .find(|old| old.name == category.name);
+            let default = self
+                .defaults
+                .categories
+                .iter()
+                .any(|default| default.name == category.name);
+            match custom {
+                Some(old) if category.display.is_some() => old.display = category.display.clone(),
+                Some(_) => {}
+                None if category.display.is_some() || !default => {
+                    self.categories.push(category.to_category())
+                }
+                None => {}
+            }
         }
 
         // convert & add entries
-        for entry in data.into_entries() {
+        for entry in data.into_entries(source) {
             self.update_or_insert(entry);
         }
 
-        // sort alphabetically
+        // sort alphabetically & update index positions
         self.data.sort_by(|a, b| a.def.name().cmp(b.def.name()));
+        self.refresh_index();
     }
 
     /// Attempts to load custom definitions from a given file.
-    pub fn try_load(&mut self, path: impl AsRef<Path>) -> Result<(), LoadError> {
-        // read file
-        let content = fs::read_to_string(path).map_err(|err| match err.kind() {
-            io::ErrorKind::NotFound => LoadError::NotFound,
-            _ => LoadError::FailedToRead,
-        })?;
-
-        // parse & add data
-        let data = parse_jsonc(&content).ok_or(LoadError::InvalidJSON)?;
-        self.add_data(data);
+    ///
+    /// Invalid entries are skipped and returned as errors.
+    pub fn try_load(
+        &mut self,
+        path: impl AsRef<Path>,
+        source: DefinitionSource,
+    ) -> Result<Vec<EntryError>, LoadError> {
+        let (data, errors) = DefData::load_file(path)?;
+        self.add_data(data, source);
 
-        Ok(())
+        Ok(errors)
     }
 
     /// Returns the kind for the given buff id & name.
+    ///
+    /// Buffs without definition are checked against the ignore name rules.
+    /// Unknown buffs are detected by name in the current client language.
+    /// Names from all languages are checked if the language is unknown.
     pub fn buff_kind(&self, id: u32, name: Option<&str>) -> BuffKind {
         if let Some(def) = self.definition(id) {
             match def {
                 DefinitionKind::Food(data) => BuffKind::Food(Some(data)),
                 DefinitionKind::Util(data) => BuffKind::Util(Some(data)),
-                DefinitionKind::Ignore => BuffKind::Ignore,
+                DefinitionKind::Category(category, data) => BuffKind::Category(category, data),
+                DefinitionKind::Ignore(reason) => BuffKind::Ignore(reason.as_deref()),
             }
-        } else {
-            // FIXME: "nourishment" & "enhancement" buff names need adjustment for other client languages
-            match name {
-                Some("Nourishment") => BuffKind::Food(None),
-                Some("Enhancement") => BuffKind::Util(None),
-                _ => BuffKind::Unknown,
+        } else if let Some(name) = name {
+            if let Some(rule) = self.ignore_name(name) {
+                return BuffKind::Ignore(rule.reason.as_deref());
             }
+
+            CONSUMABLE_NAMES
+                .iter()
+                .filter(|(lang, ..)| self.language.is_none_or(|language| language == *lang))
+                .find_map(|(_, food, util)| {
+                    if name == *food {
+                        Some(BuffKind::Food(None))
+                    } else if name == *util {
+                        Some(BuffKind::Util(None))
+                    } else {
+                        None
+                    }
+                })
+                .unwrap_or(BuffKind::Unknown)
+        } else {
+            BuffKind::Unknown
         }
     }
 
-    /// Returns the definition for the buff with the given id.
+    /// Returns the first ignore rule matching the given buff name.
+    pub fn ignore_name(&self, name: &str) -> Option<&IgnoreName> {
+        self.ignore_names().find(|rule| rule.matches(name))
+    }
+
+    /// Returns all ignore rules matching buff names.
+    ///
+    /// Default rules come first, custom rules replace default rules with the same name.
+    pub fn ignore_names(&self) -> impl Iterator<Item = &IgnoreName> + Clone {
+        let custom = |name: &str| self.ignore_names.iter().find(|rule| rule.name == name);
+        self.defaults
+            .ignore_names
+            .iter()
+            .map(move |rule| custom(&rule.name).unwrap_or(rule))
+            .chain(self.ignore_names.iter().filter(move |rule| {
+                !self
+                    .defaults
+                    .ignore_names
+                    .iter()
+                    .any(|default| default.name == rule.name)
+            }))
+    }
+
+    /// Returns all consumable categories.
+    ///
+    /// Default categories come first, custom categories replace default categories with the same name.
+    pub fn categories(&self) -> impl Iterator<Item = &Category> + Clone {
+        let custom = |name: &str| {
+            self.categories
+                .iter()
+                .find(|category| category.name == name)
+        };
+        self.defaults
+            .categories
+            .iter()
+            .map(move |category| custom(&category.name).unwrap_or(category))
+            .chain(self.categories.iter().filter(move |category| {
+                !self
+                    .defaults
+                    .categories
+                    .iter()
+                    .any(|default| default.name == category.name)
+            }))
+    }
+
+    /// Returns the consumable category with the given name.
+    pub fn category(&self, name: &str) -> Option<&Category> {
+        self.categories().find(|category| category.name == name)
+    }
+
+    /// Returns the definition for the buff with the given id or alias.
     pub fn definition(&self, buff_id: u32) -> Option<&DefinitionKind> {
-        self.data.iter().find_map(|entry| {
-            if entry.id == buff_id {
-                Some(&entry.def)
-            } else {
-                None
-            }
+        self.entry(buff_id).map(|entry| &entry.def)
+    }
+
+    /// Returns the id of the definition the given buff id or alias belongs to.
+    ///
+    /// Unknown ids are returned unchanged.
+    pub fn resolve_id(&self, buff_id: u32) -> u32 {
+        self.entry(buff_id).map_or(buff_id, |entry| entry.id)
+    }
+
+    /// Checks whether the buff ids belong to the same definition.
+    pub fn is_same(&self, a: u32, b: u32) -> bool {
+        a == b || self.resolve_id(a) == self.resolve_id(b)
+    }
+
+    /// Returns the duration in milliseconds for the buff with the given id, if known.
+    pub fn duration_ms(&self, buff_id: u32) -> Option<u64> {
+        self.definition(buff_id)
+            .and_then(|def| def.data())
+            .and_then(|data| data.duration_ms())
+    }
+
+    /// Returns the number of definition entries from the given source.
+    pub fn count_source(&self, source: DefinitionSource) -> usize {
+        self.entries()
+            .filter(|entry| entry.source == source)
+            .count()
+    }
+
+    /// Returns all definition entries in alphabetical order.
+    ///
+    /// Merges the default entries not overridden with the custom entries.
+    pub fn entries(&self) -> impl Iterator<Item = &DefinitionEntry> + Clone {
+        let mut defaults = self
+            .defaults
+            .entries
+            .iter()
+            .filter(move |entry| !self.is_overridden(entry.id))
+            .peekable();
+        let mut custom = self.data.iter().peekable();
+
+        iter::from_fn(move || match (defaults.peek(), custom.peek()) {
+            (Some(default), Some(entry)) if entry.def.name() < default.def.name() => custom.next(),
+            (Some(_), _) => defaults.next(),
+            (None, _) => custom.next(),
         })
     }
 
+    /// Returns all definitions as a single [`DefData`] collection.
+    pub fn to_data(&self) -> DefData {
+        let mut data = DefData {
+            ignore_names: self.ignore_names().cloned().collect(),
+            categories: self.categories().map(CategoryData::from).collect(),
+            ..DefData::default()
+        };
+        for entry in self.entries() {
+            data.push_entry(entry);
+        }
+        data
+    }
+
     /// Returns all food definitions.
     pub fn all_food(&self) -> impl Iterator<Item = &BuffData> + Clone {
-        self.data.iter().filter_map(|entry| match &entry.def {
+        self.entries().filter_map(|entry| match &entry.def {
             DefinitionKind::Food(data) => Some(data),
             _ => None,
         })
@@ -112,7 +400,7 @@ impl Definitions {
 
     /// Returns all utility definitions.
     pub fn all_util(&self) -> impl Iterator<Item = &BuffData> + Clone {
-        self.data.iter().filter_map(|entry| match &entry.def {
+        self.entries().filter_map(|entry| match &entry.def {
             DefinitionKind::Util(data) => Some(data),
             _ => None,
         })
@@ -124,27 +412,97 @@ impl Definitions {
 pub struct DefinitionEntry {
     pub id: u32,
     pub def: DefinitionKind,
+    pub source: DefinitionSource,
 }
 
 impl DefinitionEntry {
     /// Creates a new definitions entry.
-    pub const fn new(id: u32, def: DefinitionKind) -> Self {
-        Self { id, def }
+    pub const fn new(id: u32, def: DefinitionKind, source: DefinitionSource) -> Self {
+        Self { id, def, source }
     }
 
     /// Creates a new definitions entry for a food buff.
-    pub const fn new_food(data: BuffData) -> Self {
-        Self::new(data.id, DefinitionKind::Food(data))
+    pub const fn new_food(data: BuffData, source: DefinitionSource) -> Self {
+        Self::new(data.id, DefinitionKind::Food(data), source)
     }
 
     /// Creates a new definitions entry for an utility buff.
-    pub const fn new_util(data: BuffData) -> Self {
-        Self::new(data.id, DefinitionKind::Util(data))
+    pub const fn new_util(data: BuffData, source: DefinitionSource) -> Self {
+        Self::new(data.id, DefinitionKind::Util(data), source)
+    }
+
+    /// Creates a new definitions entry for a buff in a consumable category.
+    pub const fn new_category(
+        category: Cow<'static, str>,
+        data: BuffData,
+        source: DefinitionSource,
+    ) -> Self {
+        Self::new(data.id, DefinitionKind::Category(category, data), source)
     }
 
     /// Creates a new definitions entry for an ignored buff.
-    pub const fn new_ignore(id: u32) -> Self {
-        Self::new(id, DefinitionKind::Ignore)
+    pub fn new_ignore(data: IgnoreData, source: DefinitionSource) -> Self {
+        Self::new(data.id, DefinitionKind::Ignore(data.reason), source)
+    }
+}
+
+/// Source of a buff definition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DefinitionSource {
+    /// Bundled default definitions.
+    Default,
+
+    /// Learned definitions saved from the unknown buff collector.
+    Learned,
+
+    /// Definitions pack from the definitions directory.
+    Pack,
+
+    /// Custom definitions file.
+    Custom,
+}
+
+impl DefinitionSource {
+    /// All definition sources in load order.
+    pub const ALL: [Self; 4] = [Self::Default, Self::Learned, Self::Pack, Self::Custom];
+}
+
+impl AsRef<str> for DefinitionSource {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Default => "Default",
+            Self::Learned => "Learned",
+            Self::Pack => "Pack",
+            Self::Custom => "Custom",
+        }
+    }
+}
+
+/// Custom definition overriding a default definition without need.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StaleOverride {
+    /// Buff id of the entry.
+    pub id: u32,
+
+    /// Name of the entry.
+    pub name: String,
+
+    /// Reason the override is stale.
+    pub reason: StaleReason,
+}
+
+/// Reason for an override being stale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StaleReason {
+    /// Override is identical to the default definition.
+    Identical,
+}
+
+impl AsRef<str> for StaleReason {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Identical => "identical to default",
+        }
     }
 }
 
@@ -153,25 +511,32 @@ impl DefinitionEntry {
 pub enum DefinitionKind {
     Food(BuffData),
     Util(BuffData),
-    Ignore,
+    Category(Cow<'static, str>, BuffData),
+    Ignore(Option<Cow<'static, str>>),
 }
 
 impl DefinitionKind {
     pub fn name(&self) -> &str {
         match self {
-            Self::Food(data) => data.name.as_str(),
-            Self::Util(data) => data.name.as_str(),
-            Self::Ignore => "",
+            Self::Food(data) => &data.name,
+            Self::Util(data) => &data.name,
+            Self::Category(_, data) => &data.name,
+            Self::Ignore(_) => "",
         }
     }
 
-    #[allow(unused)]
     pub fn data(&self) -> Option<&BuffData> {
         match self {
-            Self::Food(data) | Self::Util(data) => Some(data),
-            Self::Ignore => None,
+            Self::Food(data) | Self::Util(data) | Self::Category(_, data) => Some(data),
+            Self::Ignore(_) => None,
         }
     }
+
+    pub fn aliases(&self) -> &[u32] {
+        self.data()
+            .map(|data| data.aliases.as_ref())
+            .unwrap_or_default()
+    }
 }
 
 /// Buff kind.
@@ -180,19 +545,81 @@ pub enum BuffKind<'a> {
     Unknown,
     Food(Option<&'a BuffData>),
     Util(Option<&'a BuffData>),
-    Ignore,
+    Category(&'a str, &'a BuffData),
+    Ignore(Option<&'a str>),
 }
 
+impl<'a> BuffKind<'a> {
+    /// Returns the consumable slot the buff is tracked in together with its data, if any.
+    pub fn slot(&self) -> Option<(Slot<'a>, Option<&'a BuffData>)> {
+        match *self {
+            Self::Food(data) => Some((Slot::Food, data)),
+            Self::Util(data) => Some((Slot::Util, data)),
+            Self::Category(category, data) => Some((Slot::Category(category), Some(data))),
+            Self::Unknown | Self::Ignore(_) => None,
+        }
+    }
+}
+
+/// Tracked consumable slot.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Slot<'a> {
+    Food,
+    Util,
+    Category(&'a str),
+}
+
+impl Slot<'_> {
+    /// Returns the name of the slot.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Food => "Food",
+            Self::Util => "Utility",
+            Self::Category(name) => name,
+        }
+    }
+}
+
+/// Error when loading a definitions file.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum LoadError {
     NotFound,
     FailedToRead,
-    InvalidJSON,
+    InvalidJSON(JsonError),
+    InvalidCSV(String),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound => f.write_str("File not found"),
+            Self::FailedToRead => f.write_str("Failed to read file"),
+            Self::InvalidJSON(err) => write!(f, "Invalid JSON: {err}"),
+            Self::InvalidCSV(err) => write!(f, "Invalid CSV: {err}"),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::util::{has_jsonc_comments, locate_jsonc, parse_jsonc_value};
+    use std::{hint::black_box, time::Instant};
+
+    /// Generates a food definition for testing.
+    fn test_food(id: u32, name: &'static str) -> BuffData {
+        BuffData {
+            id,
+            name: name.into(),
+            names: LocalizedNames::default(),
+            stats: Cow::Borrowed(&[]),
+            display: "Test".into(),
+            rarity: Rarity::Basic,
+            duration: None,
+            item: None,
+            aliases: Cow::Borrowed(&[]),
+        }
+    }
 
     #[test]
     fn definitions() {
@@ -200,6 +627,7 @@ mod tests {
             food,
             utility,
             ignore,
+            ..
         } = DefData::with_defaults();
 
         assert!(!food.is_empty());
@@ -215,7 +643,7 @@ mod tests {
         let data = DefData::with_defaults();
 
         let mut ids = data
-            .into_entries()
+            .into_entries(DefinitionSource::Default)
             .map(|entry| entry.id)
             .collect::<Vec<_>>();
         ids.sort_unstable();
@@ -227,6 +655,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn durations() {
+        let DefData { food, utility, .. } = DefData::with_defaults();
+
+        for BuffData {
+            id, name, duration, ..
+        } in food.iter().chain(utility.iter())
+        {
+            if *id != MALNOURISHED && *id != DIMINISHED {
+                assert!(duration.is_some(), "missing duration for {name}");
+            }
+        }
+    }
+
     #[test]
     fn display_len() {
         const MAX: usize = 6;
@@ -238,4 +680,771 @@ mod tests {
             assert!(len <= MAX, "long display name {display}: {len} characters");
         }
     }
+
+    #[test]
+    fn index() {
+        let mut defs = Definitions::with_defaults();
+        let len = defs.len();
+
+        // overwrite existing & add new entry
+        defs.add_data(
+            DefData {
+                food: vec![test_food(MALNOURISHED, "Custom"), test_food(1, "AAA")],
+                ..Default::default()
+            },
+            DefinitionSource::Custom,
+        );
+        assert_eq!(defs.len(), len + 1);
+        assert!(matches!(
+            defs.definition(MALNOURISHED),
+            Some(DefinitionKind::Food(data)) if data.name == "Custom"
+        ));
+        assert!(matches!(defs.definition(1), Some(DefinitionKind::Food(_))));
+        assert!(defs.definition(0).is_none());
+
+        // index matches sorted data
+        for (i, entry) in defs.data.iter().enumerate() {
+            assert_eq!(defs.index[&entry.id], i);
+        }
+    }
+
+    #[test]
+    fn default_table() {
+        // index sorted by id & pointing to the entries
+        assert!(DEFAULTS.index.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        for entry in DEFAULTS.entries {
+            assert_eq!(DEFAULTS.get(entry.id).map(|entry| entry.id), Some(entry.id));
+        }
+
+        // defaults are borrowed, custom entries merge in alphabetically
+        let mut defs = Definitions::with_defaults();
+        assert_eq!(defs.len(), DEFAULTS.entries.len());
+        assert_eq!(defs.to_data().len(), DefData::with_defaults().len());
+        defs.add_data(
+            DefData {
+                food: vec![test_food(MALNOURISHED, "ZZZ"), test_food(1, "AAA")],
+                ignore_names: vec![IgnoreName {
+                    name: "Custom".into(),
+                    reason: None,
+                }],
+                ..Default::default()
+            },
+            DefinitionSource::Custom,
+        );
+        let names = defs
+            .entries()
+            .map(|entry| entry.def.name())
+            .collect::<Vec<_>>();
+        assert!(names.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(names.iter().filter(|name| **name == "ZZZ").count(), 1);
+        assert_eq!(defs.len(), names.len());
+        assert!(defs.ignore_name("Custom").is_some());
+        assert_eq!(defs.ignore_names().count(), DEFAULTS.ignore_names.len() + 1);
+    }
+
+    /// Benchmarks buff kind lookups for different definition counts.
+    ///
+    /// Run with `cargo test --release -- --ignored --nocapture bench`.
+    #[test]
+    #[ignore]
+    fn bench_buff_kind() {
+        const LOOKUPS: u32 = 1_000_000;
+
+        for count in [100, 1_000, 10_000] {
+            let mut defs = Definitions::empty();
+            defs.add_data(
+                DefData {
+                    food: (0..count)
+                        .map(|id| BuffData::new(id, id.to_string(), "Test"))
+                        .collect(),
+                    ..Default::default()
+                },
+                DefinitionSource::Custom,
+            );
+
+            let start = Instant::now();
+            for i in 0..LOOKUPS {
+                // mix hits & misses
+                black_box(defs.buff_kind(black_box(i % (2 * count)), Some("Nourishment")));
+            }
+            let elapsed = start.elapsed();
+
+            println!("{count} definitions: {:?} per lookup", elapsed / LOOKUPS);
+        }
+    }
+
+    #[test]
+    fn lenient() {
+        let value = parse_jsonc_value(
+            r#"{
+                "food": [
+                    { "id": 1, "name": "Valid", "display": "Valid" },
+                    { "id": 2, "display": "Missing name" },
+                    "invalid"
+                ],
+                "utility": {},
+                "ignore": [3, -4]
+            }"#,
+        )
+        .unwrap();
+        let (data, errors) = DefData::from_value_lenient(value).unwrap();
+
+        assert_eq!(data.food.len(), 1);
+        assert!(data.utility.is_empty());
+        assert_eq!(data.ignore, [IgnoreData::from(3)]);
+
+        let paths = errors
+            .iter()
+            .map(|err| err.path.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(paths, ["food[1]", "food[2]", "utility", "ignore[1]"]);
+        assert_eq!(errors[0].id, Some(2));
+    }
+
+    #[test]
+    fn error_location() {
+        let input = r#"{
+    // "food": [{ "id": 0 }],
+    "food": [
+        { "id": 1, "name": "Valid, [x]", "display": "Valid" }, /* ] */
+        { "id": 2, "display": "Missing name" }
+    ],
+    "categories": [
+        {
+            "name": "Infusion",
+            "buffs": [{ "id": 3, "name": "Writ", "display": "Writ" }, { "id": 4 }]
+        }
+    ]
+}"#;
+        let value = parse_jsonc_value(input).unwrap();
+        let (_, mut errors) = DefData::from_value_lenient(value).unwrap();
+        for err in &mut errors {
+            err.locate(input);
+        }
+
+        let locations = errors.iter().map(|err| err.location).collect::<Vec<_>>();
+        assert_eq!(locations, [Some((5, 9)), Some((10, 71))]);
+        assert_eq!(
+            errors[0].to_string(),
+            "food[1] (id 2) at line 5 column 9: missing field `name`"
+        );
+        assert_eq!(locate_jsonc(input, "categories[0].buffs"), Some((10, 22)));
+        assert_eq!(locate_jsonc(input, "food[2]"), None);
+        assert_eq!(locate_jsonc(input, "utility"), None);
+    }
+
+    #[test]
+    fn comments() {
+        assert!(has_jsonc_comments("{\n  // food\n  \"food\": []\n}"));
+        assert!(has_jsonc_comments("{ \"food\": [] /* none */ }"));
+        assert!(!has_jsonc_comments(
+            r#"{ "food": [{ "id": 1, "name": "http://a/*b*/", "display": "\"//" }] }"#
+        ));
+        assert!(!has_jsonc_comments(&DefData::default().to_json_pretty()));
+    }
+
+    #[test]
+    fn ignore_rules() {
+        let value = parse_jsonc_value(
+            r#"{
+                "ignore": [1, { "id": 2, "reason": "Proc" }],
+                "ignore_names": [
+                    { "name": "Exact" },
+                    { "name": "Bowl of * Soup", "reason": "Soup proc" }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let (data, errors) = DefData::from_value_lenient(value).unwrap();
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(
+            data.ignore,
+            [IgnoreData::from(1), IgnoreData::new(2, Some("Proc".into()))]
+        );
+
+        // exported json keeps bare ids & reasons
+        let value = parse_jsonc_value(&data.to_json_pretty()).unwrap();
+        assert_eq!(value["ignore"][0], 1);
+        assert_eq!(value["ignore"][1]["reason"], "Proc");
+        assert_eq!(value["ignore_names"][1]["name"], "Bowl of * Soup");
+
+        let mut defs = Definitions::empty();
+        defs.add_data(data, DefinitionSource::Custom);
+        assert!(matches!(
+            defs.buff_kind(2, None),
+            BuffKind::Ignore(Some("Proc"))
+        ));
+        assert!(matches!(
+            defs.buff_kind(3, Some("Exact")),
+            BuffKind::Ignore(None)
+        ));
+        assert!(matches!(
+            defs.buff_kind(3, Some("Bowl of Fancy Soup")),
+            BuffKind::Ignore(Some("Soup proc"))
+        ));
+        assert!(matches!(
+            defs.buff_kind(3, Some("Exactly")),
+            BuffKind::Unknown
+        ));
+        assert!(matches!(
+            defs.buff_kind(3, Some("Nourishment")),
+            BuffKind::Food(None)
+        ));
+    }
+
+    #[test]
+    fn wildcards() {
+        let rule = |name: &'static str| IgnoreName {
+            name: name.into(),
+            reason: None,
+        };
+
+        assert!(rule("Proc").matches("Proc"));
+        assert!(!rule("Proc").matches("Procs"));
+        assert!(rule("*").matches(""));
+        assert!(rule("* Proc").matches("Bun Proc"));
+        assert!(!rule("* Proc").matches("Bun Procs"));
+        assert!(rule("Bowl of *").matches("Bowl of "));
+        assert!(rule("a*b*c").matches("abc"));
+        assert!(rule("a*b*c").matches("axxbyyc"));
+        assert!(!rule("a*b*c").matches("acb"));
+        assert!(!rule("*ab*b").matches("ab"));
+    }
+
+    #[test]
+    fn migration() {
+        let mut value = parse_jsonc_value(
+            r#"{
+                "food": [{ "id": 1, "name": "Food", "display": "Too long" }]
+            }"#,
+        )
+        .unwrap();
+        let migration = Migration::apply(&mut value).unwrap();
+        assert_eq!(migration.from, 0);
+        assert!(migration.upgraded());
+        assert!(migration.changes.is_empty());
+        assert_eq!(value["version"], FORMAT_VERSION);
+
+        // long display names are kept & linted
+        assert_eq!(value["food"][0]["display"], "Too long");
+        let (data, _) = DefData::from_value_lenient(value.clone()).unwrap();
+        assert!(matches!(
+            data.lint(false).as_slice(),
+            [Lint::DisplayLength { id: 1, .. }]
+        ));
+
+        // current & newer versions are untouched
+        let migration = Migration::apply(&mut value).unwrap();
+        assert!(!migration.upgraded());
+        assert!(migration.changes.is_empty());
+
+        let mut value = parse_jsonc_value(
+            r#"{
+                "version": 1000,
+                "food": [{ "id": 1, "name": "Food", "display": "Too long" }]
+            }"#,
+        )
+        .unwrap();
+        assert!(Migration::apply(&mut value).unwrap().is_newer());
+        assert_eq!(value["food"][0]["display"], "Too long");
+
+        let mut value = parse_jsonc_value(r#"{ "version": "one" }"#).unwrap();
+        assert!(Migration::apply(&mut value).is_err());
+
+        // written data uses the current version
+        let data = DefData::with_defaults();
+        let value = parse_jsonc_value(&data.to_json_pretty()).unwrap();
+        assert_eq!(value["version"], FORMAT_VERSION);
+    }
+
+    #[test]
+    fn migration_v0() {
+        let migrate = |input: &str| {
+            let mut value = parse_jsonc_value(input).unwrap();
+            let migration = Migration::apply(&mut value).unwrap();
+            assert_eq!(migration.from, 0);
+            let (data, errors) = DefData::from_value_lenient(value).unwrap();
+            assert!(errors.is_empty(), "{errors:?}");
+            (data, migration.changes)
+        };
+
+        // utility entries under the old key are moved
+        let (data, changes) = migrate(
+            r#"{
+                "utility": [{ "id": 1, "name": "Stone", "display": "Power" }],
+                "util": [{ "id": 2, "name": "Oil", "display": "Heal" }]
+            }"#,
+        );
+        assert_eq!(changes, ["util: moved 1 entries to utility"]);
+        let ids = data.utility.iter().map(|util| util.id).collect::<Vec<_>>();
+        assert_eq!(ids, [1, 2]);
+
+        // stats text is split into lines
+        let (data, changes) = migrate(
+            r#"{
+                "food": [
+                    { "id": 1, "name": "Steak", "display": "Power", "stats": "+100 Power\n\n+70 Ferocity" },
+                    { "id": 2, "name": "Soup", "display": "Heal", "stats": null }
+                ]
+            }"#,
+        );
+        assert_eq!(changes, ["food[0]: converted stats text to list"]);
+        assert_eq!(
+            *data.food[0].stats,
+            [StatModifier::from("+100 Power"), "+70 Ferocity".into()]
+        );
+        assert!(data.food[1].stats.is_empty());
+
+        // rarity names are matched regardless of case
+        let (data, changes) = migrate(
+            r#"{
+                "categories": [{
+                    "name": "Infusion",
+                    "buffs": [{ "id": 1, "name": "Writ", "display": "Writ", "rarity": "ascended" }]
+                }]
+            }"#,
+        );
+        assert_eq!(
+            changes,
+            ["categories[0].buffs[0]: renamed rarity \"ascended\" to \"Ascended\""]
+        );
+        assert_eq!(data.categories[0].buffs[0].rarity, Rarity::Ascended);
+
+        // missing display names are derived from the name
+        let (data, changes) = migrate(
+            r#"{
+                "food": [{ "id": 1, "name": "Cilantro Lime Sous-Vide Steak" }],
+                "utility": [{ "id": 2, "name": "Oil", "display": "Too long" }]
+            }"#,
+        );
+        assert_eq!(changes, ["food[0]: added display \"Cilant\" from name"]);
+        assert_eq!(data.food[0].display, "Cilant");
+        assert_eq!(data.utility[0].display, "Too long");
+    }
+
+    #[test]
+    fn csv() {
+        let (data, errors) = DefData::from_csv(
+            "kind,id,name,stats,display,rarity,duration\n\
+             Food,1,\"Bowl of Soup, Deluxe\",+100 Power; 10% Might Duration,Power,Exotic,30\n\
+             utility,2,Stone,,Power,,\n\
+             ignore,3,,,,,\n\
+             food,,Missing id,,,,\n\
+             drink,5,Unknown kind,,,,\n",
+        )
+        .unwrap();
+        assert_eq!(data.food.len(), 1);
+        assert_eq!(data.food[0].name, "Bowl of Soup, Deluxe");
+        assert_eq!(data.food[0].stats.len(), 2);
+        assert_eq!(data.food[0].rarity, Rarity::Exotic);
+        assert_eq!(data.food[0].duration, Some(30));
+        assert_eq!(data.utility[0].rarity, Rarity::Basic);
+        assert_eq!(data.ignore, [IgnoreData::from(3)]);
+
+        let paths = errors
+            .iter()
+            .map(|err| err.path.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(paths, ["line 5", "line 6"]);
+        assert_eq!(errors[1].id, Some(5));
+
+        assert!(DefData::from_csv("name,stats\nFood,\n").is_err());
+
+        // exported csv loads back
+        let defs = Definitions::with_defaults();
+        let data = defs.to_data();
+        let (loaded, errors) = DefData::from_csv(&data.to_csv()).unwrap();
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(loaded.len(), defs.len());
+        for (loaded, food) in loaded.food.iter().zip(&data.food) {
+            assert_eq!(loaded.stats, food.stats);
+            assert_eq!(loaded.rarity, food.rarity);
+            assert_eq!(loaded.duration, food.duration);
+        }
+        assert_eq!(loaded.ignore, data.ignore);
+
+        // separators within stats are escaped
+        let mut food = test_food(1, "Food");
+        food.stats = vec![
+            "+10% Might Duration; +10% Fury Duration".into(),
+            "C:\\".into(),
+        ]
+        .into();
+        let data = DefData {
+            food: vec![food.clone()],
+            ..Default::default()
+        };
+        let (loaded, errors) = DefData::from_csv(&data.to_csv()).unwrap();
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(loaded.food[0].stats, food.stats);
+    }
+
+    #[test]
+    fn aliases() {
+        let mut food = test_food(1, "Food");
+        food.aliases = vec![2, 3].into();
+        let mut defs = Definitions::empty();
+        defs.add_data(
+            DefData {
+                food: vec![food, test_food(3, "Other")],
+                ..Default::default()
+            },
+            DefinitionSource::Custom,
+        );
+
+        // aliases resolve to the same data, own entries take precedence
+        assert!(matches!(defs.buff_kind(2, None), BuffKind::Food(Some(data)) if data.id == 1));
+        assert!(matches!(defs.definition(3), Some(DefinitionKind::Food(data)) if data.id == 3));
+        assert_eq!(defs.resolve_id(2), 1);
+        assert_eq!(defs.resolve_id(3), 3);
+        assert_eq!(defs.resolve_id(4), 4);
+        assert!(defs.is_same(1, 2));
+        assert!(!defs.is_same(1, 3));
+
+        // overriding an entry drops its old aliases
+        defs.add_data(
+            DefData {
+                food: vec![test_food(1, "New"), test_food(2, "Alias")],
+                ..Default::default()
+            },
+            DefinitionSource::Custom,
+        );
+        assert_eq!(defs.len(), 3);
+        assert_eq!(defs.resolve_id(2), 2);
+        for (i, entry) in defs.data.iter().enumerate() {
+            assert_eq!(defs.index[&entry.id], i);
+        }
+
+        // aliases count as duplicates
+        let mut food = test_food(1, "Food");
+        food.aliases = vec![2].into();
+        let data = DefData {
+            food: vec![food, test_food(2, "Other")],
+            ..Default::default()
+        };
+        assert!(data.lint(false).contains(&Lint::Duplicate(2)));
+
+        // aliases survive json & csv
+        let value = parse_jsonc_value(&data.to_json_pretty()).unwrap();
+        let (loaded, _) = DefData::from_value_lenient(value).unwrap();
+        assert_eq!(*loaded.food[0].aliases, [2]);
+        let (loaded, _) = DefData::from_csv(&data.to_csv()).unwrap();
+        assert_eq!(*loaded.food[0].aliases, [2]);
+    }
+
+    #[test]
+    fn categories() {
+        let value = parse_jsonc_value(
+            r#"{
+                "categories": [
+                    {
+                        "name": "Infusion",
+                        "display": "Inf",
+                        "buffs": [
+                            { "id": 1, "name": "Writ", "display": "Writ" },
+                            { "id": 2, "display": "Missing name" }
+                        ]
+                    },
+                    { "display": "Missing name" }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let (data, errors) = DefData::from_value_lenient(value).unwrap();
+        assert_eq!(data.categories.len(), 1);
+        assert_eq!(data.categories[0].buffs.len(), 1);
+        assert_eq!(data.len(), 1);
+        let paths = errors
+            .iter()
+            .map(|err| err.path.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(paths, ["categories[0].buffs[1]", "categories[1]"]);
+
+        // category buffs are tracked in their own slot
+        let mut defs = Definitions::with_defaults();
+        defs.add_data(data, DefinitionSource::Custom);
+        let kind = defs.buff_kind(1, None);
+        assert!(matches!(kind, BuffKind::Category("Infusion", data) if data.id == 1));
+        assert!(matches!(
+            kind.slot(),
+            Some((Slot::Category("Infusion"), Some(_)))
+        ));
+        assert!(matches!(
+            defs.buff_kind(MALNOURISHED, None).slot(),
+            Some((Slot::Food, _))
+        ));
+        assert_eq!(
+            defs.category("Infusion").map(Category::display_name),
+            Some("Inf")
+        );
+
+        // categories without display keep the existing display name
+        defs.add_data(
+            DefData {
+                categories: vec![CategoryData {
+                    name: "Infusion".into(),
+                    display: None,
+                    buffs: vec![test_food(3, "Other")],
+                }],
+                ..Default::default()
+            },
+            DefinitionSource::Custom,
+        );
+        assert_eq!(defs.categories().count(), DEFAULTS.categories.len() + 1);
+        assert_eq!(
+            defs.category("Infusion").map(Category::display_name),
+            Some("Inf")
+        );
+
+        // categories survive export, json & csv
+        let data = defs.to_data();
+        assert_eq!(data.category_buffs().count(), 2);
+        let value = parse_jsonc_value(&data.to_json_pretty()).unwrap();
+        let (loaded, errors) = DefData::from_value_lenient(value).unwrap();
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(loaded.categories, data.categories);
+        let (loaded, errors) = DefData::from_csv(&data.to_csv()).unwrap();
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(loaded.len(), data.len());
+        assert_eq!(loaded.categories[0].display.as_deref(), Some("Inf"));
+
+        // merging moves buffs between categories
+        let mut data = loaded;
+        data.merge(DefData {
+            food: vec![test_food(1, "Food")],
+            ..Default::default()
+        });
+        assert_eq!(data.category_buffs().count(), 1);
+        assert_eq!(data.lint(false), []);
+    }
+
+    #[test]
+    fn syntax_error() {
+        let err = parse_jsonc_value("{\n  \"food\": [}\n}").unwrap_err();
+        assert!(matches!(err, JsonError::Syntax { line: 2, .. }), "{err:?}");
+    }
+
+    #[test]
+    fn unknown_language() {
+        let mut defs = Definitions::empty();
+
+        defs.set_language(Some(Language::German));
+        let kind = defs.buff_kind(1, Some("Ernährung"));
+        assert!(matches!(kind, BuffKind::Food(None)));
+
+        defs.set_language(Some(Language::English));
+        let kind = defs.buff_kind(1, Some("Ernährung"));
+        assert!(matches!(kind, BuffKind::Unknown));
+
+        defs.set_language(None);
+        let kind = defs.buff_kind(1, Some("Mejora"));
+        assert!(matches!(kind, BuffKind::Util(None)));
+    }
+
+    #[test]
+    fn localized_names() {
+        let mut food = test_food(1, "Bowl of Soup");
+        food.names.insert("de", "Schüssel Suppe");
+
+        assert_eq!(food.name_in(Language::German), "Schüssel Suppe");
+        assert_eq!(food.name_in(Language::French), "Bowl of Soup");
+        assert_eq!(
+            food.wiki_url(Language::German),
+            "https://wiki-de.guildwars2.com/wiki/Special:Search/Schüssel Suppe"
+        );
+        assert_eq!(
+            food.wiki_url(Language::Chinese),
+            "https://wiki-en.guildwars2.com/wiki/Special:Search/Bowl of Soup"
+        );
+    }
+
+    #[test]
+    fn chat_link() {
+        let mut food = test_food(1, "Test");
+        assert_eq!(food.chat_link(), None);
+
+        food.item = Some(23029);
+        assert_eq!(food.chat_link().as_deref(), Some("[&AgH1WQAA]"));
+        assert_eq!(
+            food.wiki_url(Language::English),
+            "https://wiki-en.guildwars2.com/wiki/Special:Search/%5B%26AgH1WQAA%5D"
+        );
+    }
+
+    #[test]
+    fn export() {
+        let mut food = test_food(1, "Food");
+        food.stats = vec!["+100 Power".into(), "66% Life Steal Chance".into()].into();
+        food.duration = Some(30);
+        let mut data = DefData {
+            food: vec![food, test_food(2, "Old")],
+            utility: Vec::new(),
+            ignore: vec![3.into()],
+            ..Default::default()
+        };
+
+        // merge replaces entries with the same id
+        data.merge(DefData {
+            food: vec![test_food(3, "New")],
+            utility: vec![test_food(2, "Util")],
+            ..Default::default()
+        });
+        let names = data.food.iter().map(|food| food.name.as_ref());
+        assert_eq!(names.collect::<Vec<_>>(), ["Food", "New"]);
+        assert_eq!(data.utility.len(), 1);
+        assert!(data.ignore.is_empty());
+
+        // exported json loads back
+        let value = parse_jsonc_value(&data.to_json_pretty()).unwrap();
+        assert_eq!(value["food"][0]["stats"][0], "+100 Power");
+        assert!(value["food"][0].get("item").is_none());
+        let (loaded, errors) = DefData::from_value_lenient(value).unwrap();
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(loaded.food[0].stats, data.food[0].stats);
+        assert_eq!(loaded.food[0].duration, Some(30));
+        assert_eq!(loaded.len(), data.len());
+    }
+
+    #[test]
+    fn stale_overrides() {
+        let defaults = DefData::with_defaults();
+        let mal = defaults.food.iter().find(|food| food.id == MALNOURISHED);
+        let dim = defaults.utility.iter().find(|util| util.id == DIMINISHED);
+        let mut dim = dim.unwrap().clone();
+        dim.display = "Other".into();
+        let custom = DefData {
+            food: vec![mal.unwrap().clone(), test_food(1, "New")],
+            utility: vec![dim],
+            ignore: vec![defaults.ignore[0].clone()],
+            ..Default::default()
+        };
+
+        let stale = custom
+            .stale_overrides(&DEFAULTS)
+            .into_iter()
+            .map(|stale| (stale.id, stale.reason))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            stale,
+            [
+                (MALNOURISHED, StaleReason::Identical),
+                (defaults.ignore[0].id, StaleReason::Identical)
+            ]
+        );
+
+        // sources are kept per entry
+        let mut defs = Definitions::with_defaults();
+        let len = defs.len();
+        defs.add_data(custom, DefinitionSource::Custom);
+        assert_eq!(defs.count_source(DefinitionSource::Custom), 4);
+        assert_eq!(defs.count_source(DefinitionSource::Default), len - 3);
+    }
+
+    #[test]
+    fn lint() {
+        assert_eq!(DefData::with_defaults().lint(true), []);
+
+        let mut long = test_food(2, "Long");
+        long.display = "Too long".into();
+        let data = DefData {
+            food: vec![test_food(1, "A"), long],
+            utility: Vec::new(),
+            ignore: vec![1.into(), 1.into()],
+            ..Default::default()
+        };
+        assert_eq!(
+            data.lint(true),
+            [
+                Lint::Duplicate(1),
+                Lint::DisplayLength {
+                    id: 2,
+                    display: "Too long".into()
+                },
+                Lint::Missing(MALNOURISHED),
+                Lint::Missing(DIMINISHED),
+            ]
+        );
+        assert_eq!(data.lint(false).len(), 2);
+    }
+
+    #[test]
+    fn stat_modifiers() {
+        assert_eq!(
+            StatModifier::from("+100 Power"),
+            StatModifier::Attribute {
+                attribute: Attribute::Power,
+                amount: 100,
+                signed: true
+            }
+        );
+        assert_eq!(
+            StatModifier::from("3% Condition Damage from Precision"),
+            StatModifier::Conversion {
+                attribute: Attribute::ConditionDamage,
+                source: Attribute::Precision,
+                percent: 3.0
+            }
+        );
+        assert_eq!(
+            StatModifier::from("-10% Incoming Damage"),
+            StatModifier::Percent {
+                effect: "Incoming Damage".into(),
+                percent: -10.0,
+                signed: true
+            }
+        );
+        assert_eq!(
+            StatModifier::from("66% Life Steal Chance"),
+            StatModifier::Percent {
+                effect: "Life Steal Chance".into(),
+                percent: 66.0,
+                signed: false
+            }
+        );
+        assert_eq!(
+            StatModifier::from("+100 Power & Ferocity on Kill"),
+            StatModifier::Other("+100 Power & Ferocity on Kill".into())
+        );
+
+        for text in [
+            "+70 Condition Damage",
+            "8% Power from Concentration",
+            "-20% Incoming Condition Duration",
+            "0.6% Outgoing Healing for 100 Healing Power",
+            "66% Life Steal Chance",
+            "100 Power",
+            "Health every second",
+        ] {
+            let stat = StatModifier::from(text);
+            assert_eq!(stat.to_string(), text);
+            assert_eq!(StatModifier::from(stat.to_string().as_str()), stat);
+
+            // serialized as stat text
+            let json = serde_json::to_string(&stat).unwrap();
+            assert_eq!(json, format!("\"{text}\""));
+            assert_eq!(serde_json::from_str::<StatModifier>(&json).unwrap(), stat);
+        }
+    }
+
+    #[test]
+    fn skill_names() {
+        let mut names = SkillNames::new(2);
+        names.record(1, "First");
+        names.record(2, "");
+        assert_eq!(names.get(1), Some("First"));
+        assert_eq!(names.get(2), None);
+
+        // recorded entries are refreshed
+        names.record(2, "Second");
+        names.record(1, "Renamed");
+        names.record(3, "Third");
+        assert_eq!(names.get(1), Some("Renamed"));
+        assert_eq!(names.get(2), None);
+        assert_eq!(names.get(3), Some("Third"));
+
+        names.record(1, "Renamed");
+        names.record(4, "Fourth");
+        assert_eq!(names.get(1), Some("Renamed"));
+        assert_eq!(names.get(3), None);
+    }
 }
@@ -0,0 +1,68 @@
+use super::{EntryError, LoadError};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Definitions pack from the definitions directory.
+#[derive(Debug, Clone)]
+pub struct DefinitionsPack {
+    /// File name of the pack.
+    pub name: String,
+
+    /// Path to the pack file.
+    pub path: PathBuf,
+
+    /// Whether the pack is enabled.
+    pub enabled: bool,
+
+    /// State of loading the pack.
+    ///
+    /// Contains the skipped entries if loaded.
+    pub state: Result<Vec<EntryError>, LoadError>,
+}
+
+impl DefinitionsPack {
+    /// File extensions recognized as definitions packs.
+    pub const EXTENSIONS: &'static [&'static str] = &["json", "jsonc", "csv"];
+
+    /// Creates a new enabled pack for the given file.
+    pub fn new(name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        Self {
+            name: name.into(),
+            path: path.into(),
+            enabled: true,
+            state: Err(LoadError::NotFound),
+        }
+    }
+
+    /// Finds all packs in the given directory.
+    ///
+    /// Packs are sorted by file name, which is the order they are applied in.
+    /// Later packs overwrite entries from earlier packs.
+    pub fn find_all(dir: impl AsRef<Path>) -> io::Result<Vec<Self>> {
+        let mut packs = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file() && Self::has_pack_extension(path))
+            .filter_map(|path| {
+                let name = path.file_name()?.to_str()?.to_string();
+                Some(Self::new(name, path))
+            })
+            .collect::<Vec<_>>();
+
+        packs.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(packs)
+    }
+
+    /// Checks whether the path has a recognized pack extension.
+    fn has_pack_extension(path: &Path) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| {
+                Self::EXTENSIONS
+                    .iter()
+                    .any(|known| ext.eq_ignore_ascii_case(known))
+            })
+    }
+}
@@ -0,0 +1,70 @@
+use std::collections::{HashMap, VecDeque};
+
+/// Default amount of skill names kept in the cache.
+pub const SKILL_NAMES_CAPACITY: usize = 512;
+
+/// Bounded cache of ingame skill names by id.
+///
+/// Filled from combat events, the least recently recorded names are dropped once the capacity is reached.
+#[derive(Debug, Clone)]
+pub struct SkillNames {
+    /// Cached names by skill id.
+    names: HashMap<u32, String>,
+
+    /// Skill ids from least to most recently recorded.
+    order: VecDeque<u32>,
+
+    /// Maximum amount of cached names.
+    capacity: usize,
+}
+
+impl SkillNames {
+    /// Creates a new empty cache with the given capacity.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            names: HashMap::new(),
+            order: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// Records the name for a skill id.
+    ///
+    /// Already cached ids are refreshed as most recent. Empty names are ignored.
+    pub fn record(&mut self, id: u32, name: &str) {
+        if name.is_empty() || self.capacity == 0 {
+            return;
+        }
+        match self.names.get_mut(&id) {
+            Some(cached) => {
+                if cached != name {
+                    *cached = name.into();
+                }
+                if let Some(index) = self.order.iter().position(|other| *other == id) {
+                    self.order.remove(index);
+                }
+                self.order.push_back(id);
+            }
+            None => {
+                while self.order.len() >= self.capacity {
+                    if let Some(oldest) = self.order.pop_front() {
+                        self.names.remove(&oldest);
+                    }
+                }
+                self.names.insert(id, name.into());
+                self.order.push_back(id);
+            }
+        }
+    }
+
+    /// Returns the cached name for a skill id.
+    pub fn get(&self, id: u32) -> Option<&str> {
+        self.names.get(&id).map(String::as_str)
+    }
+}
+
+impl Default for SkillNames {
+    fn default() -> Self {
+        Self::new(SKILL_NAMES_CAPACITY)
+    }
+}
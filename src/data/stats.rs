@@ -0,0 +1,195 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{borrow::Cow, fmt, str::FromStr};
+
+/// Stat modifier of a buff.
+///
+/// Parsed from the stat text in the definitions and serialized back as text.
+/// Whether the amount was written with an explicit sign is kept for display.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StatModifier {
+    /// Flat attribute modifier, e.g. `+100 Power`.
+    Attribute {
+        attribute: Attribute,
+        amount: i32,
+        signed: bool,
+    },
+
+    /// Attribute conversion, e.g. `3% Power from Precision`.
+    Conversion {
+        attribute: Attribute,
+        source: Attribute,
+        percent: f32,
+    },
+
+    /// Percentage effect, e.g. `-10% Incoming Damage`.
+    Percent {
+        effect: Cow<'static, str>,
+        percent: f32,
+        signed: bool,
+    },
+
+    /// Free-form stat text.
+    Other(Cow<'static, str>),
+}
+
+impl StatModifier {
+    /// Parses an optionally signed number.
+    ///
+    /// Returns the number together with whether it has an explicit sign.
+    fn parse_number<T>(text: &str) -> Option<(T, bool)>
+    where
+        T: FromStr,
+    {
+        let signed = text.starts_with(['+', '-']);
+        let number = text.strip_prefix('+').unwrap_or(text).parse().ok()?;
+        Some((number, signed))
+    }
+}
+
+impl From<&str> for StatModifier {
+    fn from(text: &str) -> Self {
+        let text = text.trim();
+
+        if let Some((percent, effect)) = text.split_once("% ") {
+            if let Some((percent, signed)) = Self::parse_number(percent) {
+                // check for conversion
+                if let Some((attribute, source)) = effect.split_once(" from ") {
+                    if let (Ok(attribute), Ok(source)) = (attribute.parse(), source.parse()) {
+                        return Self::Conversion {
+                            attribute,
+                            source,
+                            percent,
+                        };
+                    }
+                }
+
+                return Self::Percent {
+                    effect: Cow::Owned(effect.into()),
+                    percent,
+                    signed,
+                };
+            }
+        } else if let Some((amount, attribute)) = text.split_once(' ') {
+            if let (Some((amount, signed)), Ok(attribute)) =
+                (Self::parse_number(amount), attribute.parse())
+            {
+                return Self::Attribute {
+                    attribute,
+                    amount,
+                    signed,
+                };
+            }
+        }
+
+        Self::Other(Cow::Owned(text.into()))
+    }
+}
+
+impl fmt::Display for StatModifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Attribute {
+                attribute,
+                amount,
+                signed: true,
+            } => write!(f, "{amount:+} {attribute}"),
+            Self::Attribute {
+                attribute, amount, ..
+            } => write!(f, "{amount} {attribute}"),
+            Self::Conversion {
+                attribute,
+                source,
+                percent,
+            } => write!(f, "{percent}% {attribute} from {source}"),
+            Self::Percent {
+                effect,
+                percent,
+                signed: true,
+            } => write!(f, "{percent:+}% {effect}"),
+            Self::Percent {
+                effect, percent, ..
+            } => write!(f, "{percent}% {effect}"),
+            Self::Other(text) => f.write_str(text),
+        }
+    }
+}
+
+impl Serialize for StatModifier {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for StatModifier {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer).map(|text| text.as_str().into())
+    }
+}
+
+/// Character attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Attribute {
+    Power,
+    Precision,
+    Toughness,
+    Vitality,
+    Ferocity,
+    ConditionDamage,
+    Expertise,
+    Concentration,
+    HealingPower,
+    AgonyResistance,
+    MagicFind,
+    FishingPower,
+    All,
+}
+
+impl Attribute {
+    /// All attributes with their display names.
+    const NAMES: [(Self, &'static str); 13] = [
+        (Self::Power, "Power"),
+        (Self::Precision, "Precision"),
+        (Self::Toughness, "Toughness"),
+        (Self::Vitality, "Vitality"),
+        (Self::Ferocity, "Ferocity"),
+        (Self::ConditionDamage, "Condition Damage"),
+        (Self::Expertise, "Expertise"),
+        (Self::Concentration, "Concentration"),
+        (Self::HealingPower, "Healing Power"),
+        (Self::AgonyResistance, "Agony Resistance"),
+        (Self::MagicFind, "Magic Find"),
+        (Self::FishingPower, "Fishing Power"),
+        (Self::All, "All Attributes"),
+    ];
+
+    /// Returns the display name of the attribute.
+    pub fn name(&self) -> &'static str {
+        Self::NAMES
+            .iter()
+            .find_map(|(attribute, name)| (attribute == self).then_some(*name))
+            .unwrap_or_default()
+    }
+}
+
+impl FromStr for Attribute {
+    type Err = ();
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::NAMES
+            .iter()
+            .find_map(|(attribute, known)| (*known == name).then_some(*attribute))
+            .ok_or(())
+    }
+}
+
+impl fmt::Display for Attribute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
@@ -1,4 +1,6 @@
-use serde::{Deserialize, Serialize};
+use super::StatModifier;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{borrow::Cow, collections::BTreeMap};
 
 /// Buff definitions data.
 #[derive(Debug, Default, Serialize, Deserialize)]
@@ -11,29 +13,190 @@ pub struct DefData {
     pub utility: Vec<BuffData>,
 
     /// Ignored buffs.
-    pub ignore: Vec<u32>,
+    pub ignore: Vec<IgnoreData>,
+
+    /// Ignore rules matching buff names.
+    pub ignore_names: Vec<IgnoreName>,
+
+    /// User-defined consumable categories.
+    pub categories: Vec<CategoryData>,
+}
+
+impl DefData {
+    /// Returns an iterator over all buffs in consumable categories together with their category name.
+    pub fn category_buffs(&self) -> impl Iterator<Item = (&str, &BuffData)> + Clone {
+        self.categories.iter().flat_map(|category| {
+            category
+                .buffs
+                .iter()
+                .map(move |buff| (category.name.as_ref(), buff))
+        })
+    }
+}
+
+/// User-defined consumable category with its buff definitions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategoryData {
+    /// Unique name of the category.
+    pub name: Cow<'static, str>,
+
+    /// Short display name in buff tracker table.
+    ///
+    /// Categories without display name keep the display name of an existing category with the same name.
+    #[serde(default)]
+    pub display: Option<Cow<'static, str>>,
+
+    /// Buff definitions in the category.
+    #[serde(default)]
+    pub buffs: Vec<BuffData>,
+}
+
+/// Consumable category declaration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Category {
+    /// Unique name of the category.
+    pub name: Cow<'static, str>,
+
+    /// Short display name in buff tracker table.
+    pub display: Option<Cow<'static, str>>,
 }
 
 /// Single buff data entry.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BuffData {
     /// Ingame buff id.
     pub id: u32,
 
     /// Full name of the buff (or item applying it).
-    pub name: String,
+    pub name: Cow<'static, str>,
+
+    /// Localized names keyed by language code.
+    ///
+    /// Missing languages fall back to the English [`BuffData::name`].
+    #[serde(default)]
+    pub names: LocalizedNames,
 
     /// Buff stats.
-    // TODO: convert to option<string> when deserializing?
+    ///
+    /// Parsed from the stat texts.
     #[serde(default)]
-    pub stats: Vec<String>,
+    pub stats: Cow<'static, [StatModifier]>,
 
     /// Short display name in buff tracker table.
-    pub display: String,
+    pub display: Cow<'static, str>,
 
     /// Rarity of the item applying the effect.
     #[serde(default)]
     pub rarity: Rarity,
+
+    /// Duration of the buff in minutes.
+    #[serde(default)]
+    pub duration: Option<u32>,
+
+    /// Ingame id of the item applying the buff.
+    #[serde(default)]
+    pub item: Option<u32>,
+
+    /// Alternative buff ids applied by the same item.
+    ///
+    /// Aliases are treated as this buff.
+    #[serde(default)]
+    pub aliases: Cow<'static, [u32]>,
+}
+
+/// Localized names keyed by language code.
+///
+/// Serialized as map from language code to name.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct LocalizedNames(pub Cow<'static, [(Cow<'static, str>, Cow<'static, str>)]>);
+
+impl LocalizedNames {
+    /// Returns whether there are no localized names.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns an iterator over all language codes & names.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(code, name)| (code.as_ref(), name.as_ref()))
+    }
+}
+
+impl Serialize for LocalizedNames {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_map(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for LocalizedNames {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let names = BTreeMap::<String, String>::deserialize(deserializer)?;
+        Ok(Self(
+            names
+                .into_iter()
+                .map(|(code, name)| (code.into(), name.into()))
+                .collect(),
+        ))
+    }
+}
+
+/// Ignored buff entry.
+///
+/// Deserialized from a bare id or an object with a reason.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IgnoreData {
+    /// Ingame buff id.
+    pub id: u32,
+
+    /// Reason for ignoring the buff.
+    pub reason: Option<Cow<'static, str>>,
+}
+
+impl<'de> Deserialize<'de> for IgnoreData {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Id(u32),
+            Entry {
+                id: u32,
+                #[serde(default)]
+                reason: Option<String>,
+            },
+        }
+
+        Ok(match Raw::deserialize(deserializer)? {
+            Raw::Id(id) => Self { id, reason: None },
+            Raw::Entry { id, reason } => Self {
+                id,
+                reason: reason.map(Into::into),
+            },
+        })
+    }
+}
+
+/// Ignore rule matching buff names.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IgnoreName {
+    /// Buff name to match.
+    ///
+    /// A `*` matches any sequence of characters.
+    pub name: Cow<'static, str>,
+
+    /// Reason for ignoring the buffs.
+    #[serde(default)]
+    pub reason: Option<Cow<'static, str>>,
 }
 
 #[derive(
@@ -49,3 +212,25 @@ pub enum Rarity {
     Ascended,
     Legendary,
 }
+
+/// Game client language.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum Language {
+    #[default]
+    #[serde(rename = "en")]
+    English,
+
+    #[serde(rename = "de")]
+    German,
+
+    #[serde(rename = "fr")]
+    French,
+
+    #[serde(rename = "es")]
+    Spanish,
+
+    #[serde(rename = "zh")]
+    Chinese,
+}
@@ -45,7 +45,7 @@ impl Demo {
             BuffState::None => "None".into(),
             BuffState::Some(buff) => {
                 if let Some(DefinitionKind::Food(food)) = defs.definition(buff) {
-                    food.name.clone().into()
+                    food.name_in(defs.display_language()).to_string().into()
                 } else {
                     "Unknown".into()
                 }
@@ -60,7 +60,7 @@ impl Demo {
             BuffState::None => "None".into(),
             BuffState::Some(buff) => {
                 if let Some(DefinitionKind::Util(util)) = defs.definition(buff) {
-                    util.name.clone().into()
+                    util.name_in(defs.display_language()).to_string().into()
                 } else {
                     "Unknown".into()
                 }
@@ -55,10 +55,15 @@ impl HasSettings for Demo {
             let Entry { data, .. } = self.tracker.players.player_mut(id).unwrap();
             data.food = TrackedBuff::new(loaded.food);
             data.util = TrackedBuff::new(loaded.util);
+            data.categories = loaded
+                .categories
+                .into_iter()
+                .map(|(name, state)| (name, TrackedBuff::new(state)))
+                .collect();
             data.custom = loaded
                 .buffs
                 .into_iter()
-                .map(|(id, state)| (id, TrackedBuff::new(state)))
+                .map(|(id, state)| (id, TrackedBuff::new(state.map(|_| 1))))
                 .collect();
         }
         self.tracker.set_visibility(loaded.tracker);
@@ -75,6 +80,9 @@ struct DemoEntry {
     #[serde(default)]
     pub util: BuffState<u32>,
 
+    #[serde(default)]
+    pub categories: BTreeMap<String, BuffState<u32>>,
+
     #[serde(default)]
     pub buffs: BTreeMap<u32, BuffState<()>>,
 }
@@ -85,11 +93,17 @@ impl From<Entry<Buffs>> for DemoEntry {
             player: entry.player,
             food: entry.data.food.state,
             util: entry.data.util.state,
+            categories: entry
+                .data
+                .categories
+                .into_iter()
+                .map(|(name, buff)| (name, buff.state))
+                .collect(),
             buffs: entry
                 .data
                 .custom
                 .into_iter()
-                .map(|(id, buff)| (id, buff.state))
+                .map(|(id, buff)| (id, buff.state.map(|_| ())))
                 .collect(),
         }
     }
@@ -61,7 +61,7 @@ impl Demo {
                 // tooltip
                 if ui.is_item_hovered() {
                     if let Some(buff) = data {
-                        render_buff_tooltip(ui, buff);
+                        render_buff_tooltip(ui, buff, defs.display_language());
                     }
                 }
             }
@@ -71,7 +71,7 @@ impl Demo {
 }
 
 impl Component<Props<'_>> for Demo {
-    fn render(&mut self, ui: &Ui, (defs, custom): Props) {
+    fn render(&mut self, ui: &Ui, (defs, custom, names): Props) {
         // initialize data
         if self.all_foods.is_empty() {
             self.all_foods = SPECIAL_BUFFS
@@ -106,9 +106,16 @@ impl Component<Props<'_>> for Demo {
                 self.reminder.trigger_util();
             }
 
+            for category in defs.categories() {
+                ui.same_line_with_spacing(0.0, 5.0);
+                if ui.button(category.display_name()) {
+                    self.reminder.trigger_category(&category.name);
+                }
+            }
+
             for remind in custom {
                 ui.same_line_with_spacing(0.0, 5.0);
-                if ui.button(remind.display_name()) {
+                if ui.button(remind.resolved_name(names)) {
                     self.reminder.trigger_custom(remind.id);
                 }
             }
@@ -212,7 +219,7 @@ impl Component<Props<'_>> for Demo {
                             data.custom.insert(
                                 custom.id,
                                 TrackedBuff::new(if applied {
-                                    BuffState::Some(())
+                                    BuffState::Some(1)
                                 } else {
                                     BuffState::None
                                 }),
@@ -250,8 +257,8 @@ impl Component<Props<'_>> for Demo {
         }
 
         // render children
-        self.reminder.render(ui, ());
-        self.tracker.render(ui, (defs, custom));
+        self.reminder.render(ui, names);
+        self.tracker.render(ui, (defs, custom, names));
     }
 }
 
@@ -0,0 +1,388 @@
+pub mod ui;
+
+use crate::data::{
+    BuffData, Category, CategoryData, DefData, DefinitionEntry, DefinitionKind, IgnoreData,
+    IgnoreName, StatModifier,
+};
+use std::{borrow::Cow, collections::HashSet};
+use strum::{AsRefStr, EnumIter};
+
+/// Editor for the custom definitions file.
+#[derive(Debug)]
+pub struct Editor {
+    /// Entries of the custom definitions file.
+    ///
+    /// [`None`] if not loaded.
+    entries: Option<Vec<EditorEntry>>,
+
+    /// Ignore rules matching buff names.
+    ///
+    /// Not editable, kept when saving.
+    ignore_names: Vec<IgnoreName>,
+
+    /// Consumable category declarations.
+    ///
+    /// Not editable, kept when saving.
+    categories: Vec<Category>,
+
+    /// Error when loading the custom definitions file.
+    error: Option<String>,
+
+    /// Index of the selected entry.
+    selected: Option<usize>,
+
+    /// Current search contents.
+    search: String,
+
+    /// Whether there are unsaved changes.
+    dirty: bool,
+
+    /// Whether the custom definitions file contains comments lost on save.
+    comments: bool,
+
+    /// Confirmation for saving over comments.
+    save_confirm: bool,
+
+    /// Whether saving was requested.
+    save: bool,
+}
+
+impl Editor {
+    /// Creates a new editor.
+    pub const fn new() -> Self {
+        Self {
+            entries: None,
+            ignore_names: Vec::new(),
+            categories: Vec::new(),
+            error: None,
+            selected: None,
+            search: String::new(),
+            dirty: false,
+            comments: false,
+            save_confirm: false,
+            save: false,
+        }
+    }
+
+    /// Returns whether the custom definitions are loaded.
+    pub fn is_loaded(&self) -> bool {
+        self.entries.is_some() || self.error.is_some()
+    }
+
+    /// Returns whether there are unsaved changes.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Loads the custom definitions data for editing.
+    ///
+    /// `comments` marks a file containing comments, which requires confirmation to save.
+    pub fn load(&mut self, data: Result<DefData, String>, comments: bool) {
+        match data {
+            Ok(data) => {
+                self.entries = Some(
+                    data.food
+                        .into_iter()
+                        .map(|food| EditorEntry::new(EditorKind::Food, food))
+                        .chain(
+                            data.utility
+                                .into_iter()
+                                .map(|util| EditorEntry::new(EditorKind::Util, util)),
+                        )
+                        .chain(data.categories.iter().flat_map(|category| {
+                            category
+                                .buffs
+                                .iter()
+                                .map(|buff| EditorEntry::new_category(&category.name, buff.clone()))
+                        }))
+                        .chain(data.ignore.into_iter().map(EditorEntry::new_ignore))
+                        .collect(),
+                );
+                self.ignore_names = data.ignore_names;
+                self.categories = data
+                    .categories
+                    .iter()
+                    .map(CategoryData::to_category)
+                    .collect();
+                self.error = None;
+            }
+            Err(err) => {
+                self.entries = None;
+                self.ignore_names.clear();
+                self.categories.clear();
+                self.error = Some(err);
+            }
+        }
+        self.selected = None;
+        self.dirty = false;
+        self.comments = comments;
+        self.save_confirm = false;
+    }
+
+    /// Discards the loaded custom definitions.
+    pub fn unload(&mut self) {
+        self.entries = None;
+        self.ignore_names.clear();
+        self.categories.clear();
+        self.error = None;
+        self.selected = None;
+        self.dirty = false;
+        self.comments = false;
+        self.save_confirm = false;
+    }
+
+    /// Marks the current entries as saved.
+    pub fn saved(&mut self) {
+        self.dirty = false;
+        self.comments = false;
+    }
+
+    /// Takes the definitions data requested to be saved.
+    pub fn take_save(&mut self) -> Option<DefData> {
+        if self.save {
+            self.save = false;
+            self.to_data()
+        } else {
+            None
+        }
+    }
+
+    /// Converts the entries into definitions data.
+    pub fn to_data(&self) -> Option<DefData> {
+        let entries = self.entries.as_ref()?;
+        let mut data = DefData {
+            ignore_names: self.ignore_names.clone(),
+            categories: self.categories.iter().map(CategoryData::from).collect(),
+            ..DefData::default()
+        };
+        for entry in entries {
+            match entry.kind {
+                EditorKind::Food => data.food.push(entry.to_buff_data()),
+                EditorKind::Util => data.utility.push(entry.to_buff_data()),
+                EditorKind::Category => {
+                    let name = entry.category.trim();
+                    match data
+                        .categories
+                        .iter_mut()
+                        .find(|category| category.name == name)
+                    {
+                        Some(category) => category.buffs.push(entry.to_buff_data()),
+                        None => data.categories.push(CategoryData {
+                            name: name.to_owned().into(),
+                            display: None,
+                            buffs: vec![entry.to_buff_data()],
+                        }),
+                    }
+                }
+                EditorKind::Ignore => data.ignore.push(entry.to_ignore_data()),
+            }
+        }
+        Some(data)
+    }
+
+    /// Checks the entries for problems preventing a save.
+    pub fn validate(&self) -> Result<(), String> {
+        let mut ids = HashSet::new();
+        for entry in self.entries.iter().flatten() {
+            if entry.data.id == 0 {
+                return Err("Entry with id 0".into());
+            }
+            if !ids.insert(entry.data.id) {
+                return Err(format!("Duplicate id {}", entry.data.id));
+            }
+            for alias in entry.parse_aliases()? {
+                if !ids.insert(alias) {
+                    return Err(format!("Duplicate id {alias}"));
+                }
+            }
+            if entry.kind != EditorKind::Ignore && entry.data.name.is_empty() {
+                return Err(format!("Missing name for id {}", entry.data.id));
+            }
+            if entry.kind == EditorKind::Category && entry.category.trim().is_empty() {
+                return Err(format!("Missing category for id {}", entry.data.id));
+            }
+        }
+        Ok(())
+    }
+
+    /// Selects the custom entry for a definition, adding a copy of the definition if necessary.
+    pub fn edit(&mut self, def: &DefinitionEntry) {
+        if let Some(entries) = &mut self.entries {
+            let index = match entries.iter().position(|entry| entry.data.id == def.id) {
+                Some(index) => index,
+                None => {
+                    entries.push(match &def.def {
+                        DefinitionKind::Food(data) => {
+                            EditorEntry::new(EditorKind::Food, data.clone())
+                        }
+                        DefinitionKind::Util(data) => {
+                            EditorEntry::new(EditorKind::Util, data.clone())
+                        }
+                        DefinitionKind::Category(category, data) => {
+                            EditorEntry::new_category(category, data.clone())
+                        }
+                        DefinitionKind::Ignore(reason) => {
+                            EditorEntry::new_ignore(IgnoreData::new(def.id, reason.clone()))
+                        }
+                    });
+                    self.dirty = true;
+                    entries.len() - 1
+                }
+            };
+            self.selected = Some(index);
+        }
+    }
+
+    /// Adds a new empty entry of the given kind.
+    pub fn add(&mut self, kind: EditorKind) {
+        if let Some(entries) = &mut self.entries {
+            entries.push(EditorEntry::new(kind, BuffData::new(0, "", "")));
+            self.selected = Some(entries.len() - 1);
+            self.dirty = true;
+        }
+    }
+
+    /// Removes the selected entry.
+    pub fn remove_selected(&mut self) {
+        if let (Some(entries), Some(index)) = (&mut self.entries, self.selected.take()) {
+            entries.remove(index);
+            self.dirty = true;
+        }
+    }
+}
+
+impl Default for Editor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Kind of an edited definition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, AsRefStr, EnumIter)]
+pub enum EditorKind {
+    Food,
+    #[strum(serialize = "Utility")]
+    Util,
+    Category,
+    Ignore,
+}
+
+/// Edited definitions entry.
+#[derive(Debug, Clone)]
+pub struct EditorEntry {
+    /// Kind of definition.
+    pub kind: EditorKind,
+
+    /// Buff data.
+    ///
+    /// Only the id is used for ignored buffs.
+    pub data: BuffData,
+
+    /// Stat texts, one per line.
+    pub stats: String,
+
+    /// Alias ids, separated by commas.
+    pub aliases: String,
+
+    /// Reason for ignored buffs.
+    pub reason: String,
+
+    /// Category name for buffs in a consumable category.
+    pub category: String,
+}
+
+impl EditorEntry {
+    /// Creates a new entry from buff data.
+    pub fn new(kind: EditorKind, data: BuffData) -> Self {
+        let stats = data
+            .stats
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("\n");
+        let aliases = data
+            .aliases
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        Self {
+            kind,
+            data,
+            stats,
+            aliases,
+            reason: String::new(),
+            category: String::new(),
+        }
+    }
+
+    /// Creates a new entry for a buff in a consumable category.
+    pub fn new_category(category: &str, data: BuffData) -> Self {
+        Self {
+            category: category.into(),
+            ..Self::new(EditorKind::Category, data)
+        }
+    }
+
+    /// Returns the name of the entry kind.
+    ///
+    /// Buffs in a consumable category use the category name.
+    pub fn kind_name(&self) -> &str {
+        match self.kind {
+            EditorKind::Category if !self.category.is_empty() => &self.category,
+            _ => self.kind.as_ref(),
+        }
+    }
+
+    /// Creates a new entry for an ignored buff.
+    pub fn new_ignore(ignore: IgnoreData) -> Self {
+        Self {
+            reason: ignore.reason.map(Cow::into_owned).unwrap_or_default(),
+            ..Self::new(EditorKind::Ignore, BuffData::new(ignore.id, "", ""))
+        }
+    }
+
+    /// Parses the alias ids.
+    pub fn parse_aliases(&self) -> Result<Vec<u32>, String> {
+        if self.kind == EditorKind::Ignore {
+            return Ok(Vec::new());
+        }
+        self.aliases
+            .split(',')
+            .map(str::trim)
+            .filter(|alias| !alias.is_empty())
+            .map(|alias| {
+                alias
+                    .parse()
+                    .map_err(|_| format!("Invalid alias \"{alias}\" for id {}", self.data.id))
+            })
+            .collect()
+    }
+
+    /// Converts the entry into buff data with parsed stats & aliases.
+    ///
+    /// Invalid aliases are dropped.
+    pub fn to_buff_data(&self) -> BuffData {
+        BuffData {
+            aliases: self.parse_aliases().unwrap_or_default().into(),
+            stats: self
+                .stats
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(StatModifier::from)
+                .collect::<Vec<_>>()
+                .into(),
+            ..self.data.clone()
+        }
+    }
+
+    /// Converts the entry into ignore data.
+    pub fn to_ignore_data(&self) -> IgnoreData {
+        let reason = self.reason.trim();
+        IgnoreData::new(
+            self.data.id,
+            (!reason.is_empty()).then(|| reason.to_owned().into()),
+        )
+    }
+}
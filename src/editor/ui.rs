@@ -0,0 +1,310 @@
+use super::{Editor, EditorKind};
+use crate::{
+    combo_ui::{render_combo, render_enum_combo},
+    data::{DefinitionKind, Definitions, Rarity},
+};
+use arc_util::{
+    colors::{GREY, RED, YELLOW},
+    ui::{render, Component},
+};
+use arcdps::{
+    exports::{self, CoreColor},
+    imgui::{Selectable, SelectableFlags, TableColumnSetup, TableFlags, Ui},
+};
+
+pub type Props<'p> = &'p Definitions;
+
+impl Editor {
+    /// Renders the form for the selected entry.
+    fn render_form(&mut self, ui: &Ui) {
+        let colors = exports::colors();
+        let yellow = colors.core(CoreColor::LightYellow).unwrap_or(YELLOW);
+        let input_width = render::ch_width(ui, 24);
+
+        let (Some(entries), Some(index)) = (&mut self.entries, self.selected) else {
+            return;
+        };
+        let entry = &mut entries[index];
+        let mut changed = false;
+
+        ui.set_next_item_width(input_width);
+        changed |= render_enum_combo(ui, "Kind", &mut entry.kind);
+
+        let mut id = entry.data.id.try_into().unwrap_or(0);
+        ui.set_next_item_width(input_width);
+        if ui.input_int("Id", &mut id).step(0).build() {
+            entry.data.id = id.max(0) as u32;
+            changed = true;
+        }
+
+        if entry.kind == EditorKind::Ignore {
+            ui.set_next_item_width(input_width);
+            changed |= ui.input_text("Reason", &mut entry.reason).build();
+        } else {
+            if entry.kind == EditorKind::Category {
+                ui.set_next_item_width(input_width);
+                changed |= ui.input_text("Category", &mut entry.category).build();
+                if ui.is_item_hovered() {
+                    ui.tooltip_text("Name of the consumable category, tracked in its own column.");
+                }
+            }
+
+            ui.set_next_item_width(input_width);
+            changed |= ui.input_text("Name", entry.data.name.to_mut()).build();
+
+            ui.set_next_item_width(input_width);
+            changed |= ui
+                .input_text("Display", entry.data.display.to_mut())
+                .build();
+            if entry.data.display.chars().count() > 6 {
+                ui.text_colored(yellow, "Display names over 6 characters may not fit");
+            }
+
+            ui.set_next_item_width(input_width);
+            changed |= render_combo(
+                ui,
+                "Rarity",
+                Rarity::ALL,
+                &mut entry.data.rarity,
+                |rarity| format!("{rarity:?}").into(),
+                Rarity::color,
+            );
+
+            let mut duration = entry.data.duration.unwrap_or(0).try_into().unwrap_or(0);
+            ui.set_next_item_width(input_width);
+            if ui
+                .input_int("Duration (min)", &mut duration)
+                .step(10)
+                .build()
+            {
+                entry.data.duration = u32::try_from(duration).ok().filter(|min| *min > 0);
+                changed = true;
+            }
+
+            changed |= ui
+                .input_text_multiline("Stats", &mut entry.stats, [input_width, 60.0])
+                .build();
+            if ui.is_item_hovered() {
+                ui.tooltip_text("One stat per line, e.g. \"+100 Power\".");
+            }
+
+            ui.set_next_item_width(input_width);
+            changed |= ui.input_text("Aliases", &mut entry.aliases).build();
+            if ui.is_item_hovered() {
+                ui.tooltip_text("Other buff ids applied by the same item, separated by commas.");
+            }
+        }
+
+        if changed {
+            self.dirty = true;
+        }
+    }
+}
+
+impl Component<Props<'_>> for Editor {
+    fn render(&mut self, ui: &Ui, defs: Props) {
+        const SPACING: f32 = 5.0;
+
+        let colors = exports::colors();
+        let grey = colors.core(CoreColor::MediumGrey).unwrap_or(GREY);
+        let red = colors.core(CoreColor::LightRed).unwrap_or(RED);
+        let yellow = colors.core(CoreColor::LightYellow).unwrap_or(YELLOW);
+
+        if let Some(err) = &self.error {
+            ui.text_colored(red, "Unable to edit custom definitions");
+            ui.text_colored(red, err);
+            if ui.button("Retry") {
+                self.unload();
+            }
+            return;
+        }
+
+        let Some(entries) = &self.entries else {
+            return;
+        };
+
+        // custom entries
+        ui.text(format!("Custom entries: {}", entries.len()));
+        let mut clicked = None;
+        if let Some(_table) = ui.begin_table_header_with_sizing(
+            "##editor-custom",
+            [
+                TableColumnSetup::new("Kind"),
+                TableColumnSetup::new("Id"),
+                TableColumnSetup::new("Name"),
+            ],
+            TableFlags::SIZING_STRETCH_PROP | TableFlags::PAD_OUTER_X | TableFlags::SCROLL_Y,
+            [0.0, 150.0],
+            0.0,
+        ) {
+            for (i, entry) in entries.iter().enumerate() {
+                ui.table_next_row();
+
+                ui.table_next_column();
+                if Selectable::new(format!("{}##custom-{i}", entry.kind_name()))
+                    .selected(self.selected == Some(i))
+                    .flags(SelectableFlags::SPAN_ALL_COLUMNS)
+                    .build(ui)
+                {
+                    clicked = Some(i);
+                }
+
+                ui.table_next_column();
+                ui.text(entry.data.id.to_string());
+
+                ui.table_next_column();
+                if entry.kind == EditorKind::Ignore {
+                    ui.text_colored(grey, &entry.reason);
+                } else {
+                    ui.text(&entry.data.name);
+                }
+            }
+        }
+        if clicked.is_some() {
+            self.selected = clicked;
+        }
+
+        // entry actions
+        if ui.button("Add Food") {
+            self.add(EditorKind::Food);
+        }
+        ui.same_line_with_spacing(0.0, SPACING);
+        if ui.button("Add Utility") {
+            self.add(EditorKind::Util);
+        }
+        ui.same_line_with_spacing(0.0, SPACING);
+        if ui.button("Add Category") {
+            self.add(EditorKind::Category);
+        }
+        ui.same_line_with_spacing(0.0, SPACING);
+        if ui.button("Add Ignore") {
+            self.add(EditorKind::Ignore);
+        }
+        ui.same_line_with_spacing(0.0, SPACING);
+        if ui.button("Remove") {
+            self.remove_selected();
+        }
+
+        // selected entry
+        ui.spacing();
+        self.render_form(ui);
+
+        // save & revert
+        ui.spacing();
+        let valid = self.validate();
+        if self.comments {
+            if render::reset_button(ui, "Save", &mut self.save_confirm) && valid.is_ok() {
+                self.save = true;
+            }
+            if !self.save_confirm && ui.is_item_hovered() {
+                ui.tooltip_text("Write the custom entries to the custom definitions file.\nComments in the file are not preserved, the previous file is kept as backup.");
+            }
+        } else {
+            if ui.button("Save") && valid.is_ok() {
+                self.save = true;
+            }
+            if ui.is_item_hovered() {
+                ui.tooltip_text("Write the custom entries to the custom definitions file.");
+            }
+        }
+        ui.same_line_with_spacing(0.0, SPACING);
+        if ui.button("Revert") {
+            self.unload();
+        }
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Discard unsaved changes.");
+        }
+        if self.comments {
+            ui.text_colored(
+                yellow,
+                "Custom definitions file contains comments, which are lost on save",
+            );
+        }
+        match valid {
+            Err(err) => ui.text_colored(red, err),
+            Ok(()) if self.dirty => ui.text_colored(yellow, "Unsaved changes"),
+            Ok(()) => {}
+        }
+
+        // merged definitions
+        ui.spacing();
+        ui.separator();
+        ui.spacing();
+        ui.set_next_item_width(render::ch_width(ui, 24));
+        ui.input_text("Search", &mut self.search).build();
+        let search = self.search.to_lowercase();
+
+        let mut edit = None;
+        if let Some(_table) = ui.begin_table_header_with_sizing(
+            "##editor-defs",
+            [
+                TableColumnSetup::new("Kind"),
+                TableColumnSetup::new("Id"),
+                TableColumnSetup::new("Name"),
+                TableColumnSetup::new("Source"),
+            ],
+            TableFlags::SIZING_STRETCH_PROP | TableFlags::PAD_OUTER_X | TableFlags::SCROLL_Y,
+            [0.0, 200.0],
+            0.0,
+        ) {
+            let entries = defs.entries().filter(|entry| {
+                search.is_empty()
+                    || entry.id.to_string().contains(&search)
+                    || entry.def.name().to_lowercase().contains(&search)
+            });
+            for entry in entries {
+                ui.table_next_row();
+
+                ui.table_next_column();
+                let kind = match &entry.def {
+                    DefinitionKind::Food(_) => "Food",
+                    DefinitionKind::Util(_) => "Utility",
+                    DefinitionKind::Category(category, _) => category.as_ref(),
+                    DefinitionKind::Ignore(_) => "Ignore",
+                };
+                if Selectable::new(format!("{kind}##def-{}", entry.id))
+                    .flags(SelectableFlags::SPAN_ALL_COLUMNS)
+                    .build(ui)
+                {
+                    edit = Some(entry);
+                }
+                if ui.is_item_hovered() {
+                    ui.tooltip_text("Click to edit as custom entry.");
+                }
+
+                ui.table_next_column();
+                ui.text(entry.id.to_string());
+
+                ui.table_next_column();
+                match &entry.def {
+                    DefinitionKind::Ignore(reason) => {
+                        ui.text_colored(grey, reason.as_deref().unwrap_or_default())
+                    }
+                    def => ui.text(def.name()),
+                }
+
+                ui.table_next_column();
+                ui.text(entry.source.as_ref());
+            }
+        }
+        if let Some(entry) = edit {
+            self.edit(entry);
+        }
+
+        // ignore name rules
+        let rules = defs.ignore_names().collect::<Vec<_>>();
+        if !rules.is_empty() {
+            ui.text(format!("Ignore name rules: {}", rules.len()));
+            if ui.is_item_hovered() {
+                ui.tooltip(|| {
+                    for rule in rules {
+                        match &rule.reason {
+                            Some(reason) => ui.text(format!("{} ({reason})", rule.name)),
+                            None => ui.text(&rule.name),
+                        }
+                    }
+                });
+            }
+        }
+    }
+}
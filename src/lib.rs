@@ -1,12 +1,15 @@
 mod assets;
 mod buff_ui;
 mod builds;
+mod collector;
 mod combo_ui;
-mod data;
+pub mod data;
+mod editor;
 mod plugin;
+mod pulls;
 mod reminder;
 mod tracking;
-mod util;
+pub mod util;
 
 #[cfg(feature = "demo")]
 mod demo;
@@ -14,7 +17,7 @@ mod demo;
 use arcdps::{
     extras::{ExtrasAddonInfo, UserInfoIter},
     imgui::Ui,
-    Agent, Event,
+    Agent, Event, Language,
 };
 use plugin::Plugin;
 
@@ -31,6 +34,7 @@ arcdps::export! {
     wnd_filter,
     extras_init,
     extras_squad_update,
+    extras_language_changed,
 }
 
 fn init() -> Result<(), String> {
@@ -55,6 +59,7 @@ fn combat(
 }
 
 fn imgui(ui: &Ui, not_loading_or_character_selection: bool) {
+    Plugin::check_defs_changes();
     Plugin::lock().render_windows(ui, not_loading_or_character_selection)
 }
 
@@ -77,3 +82,7 @@ fn extras_init(addon_info: ExtrasAddonInfo, account_name: Option<&str>) {
 fn extras_squad_update(users: UserInfoIter) {
     Plugin::lock().extras_squad_update(users)
 }
+
+fn extras_language_changed(language: Language) {
+    Plugin::lock().extras_language_changed(language)
+}
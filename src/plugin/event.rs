@@ -1,12 +1,16 @@
 use super::{ExtrasState, Plugin};
-use crate::{data::BuffKind, tracking::Sorting};
+use crate::{
+    collector::UnknownKind,
+    data::{BuffKind, Slot},
+    tracking::{history::HistoryBuff, Sorting},
+};
 use arc_util::{
     api::delta_time,
     tracking::{Entry, Player},
 };
 use arcdps::{
     extras::{ExtrasAddonInfo, UserInfo, UserInfoIter, UserRole},
-    Activation, Agent, BuffRemove, Event, StateChange,
+    Activation, Agent, BuffRemove, Event, Language, StateChange,
 };
 use log::{debug, info, log_enabled, Level};
 
@@ -40,15 +44,19 @@ impl Plugin {
 
                         // change buffs to none, initial buffs should be reported right after
                         for entry in plugin.tracker.players.iter_mut() {
+                            entry.data.start_encounter(event.time);
                             entry.data.unset_to_none(
                                 event.time,
+                                plugin
+                                    .defs
+                                    .categories()
+                                    .map(|category| category.name.as_ref()),
                                 plugin.reminder.all_custom().iter().map(|remind| remind.id),
                             );
                         }
 
-                        // refresh if food or util sorting
-                        plugin.tracker.refresh_sort_if(Sorting::Food);
-                        plugin.tracker.refresh_sort_if(Sorting::Util);
+                        // refresh if consumable sorting
+                        plugin.tracker.refresh_sort_if_any_slot();
 
                         plugin.reminder.start_encounter(target_id, event.time);
                     }
@@ -69,7 +77,17 @@ impl Plugin {
                         let plugin = guard.as_mut();
                         let target_id = event.src_agent;
                         debug!("Log for id {} ended", target_id);
-                        plugin.reminder.end_encounter(&plugin.tracker.players);
+                        for entry in plugin.tracker.players.iter_mut() {
+                            entry.data.end_encounter(event.time);
+                        }
+                        plugin.pulls.finish(&plugin.tracker.players);
+                        plugin.reminder.end_encounter(
+                            &plugin.tracker.players,
+                            plugin
+                                .defs
+                                .categories()
+                                .map(|category| category.name.as_ref()),
+                        );
                     }
 
                     StateChange::None | StateChange::ApiDelayed | StateChange::BuffInitial => {
@@ -89,16 +107,18 @@ impl Plugin {
                                     }
                                 }
 
-                                // remove on all or single manual
-                                BuffRemove::All | BuffRemove::Manual => Self::lock().buff_remove(
-                                    src.id,
-                                    event.skill_id,
-                                    skill_name,
-                                    event,
-                                    event_id,
-                                ),
+                                // remove on all, single stack or single manual
+                                BuffRemove::All | BuffRemove::Single | BuffRemove::Manual => {
+                                    Self::lock().buff_remove(
+                                        src.id,
+                                        event.skill_id,
+                                        skill_name,
+                                        event,
+                                        event_id,
+                                    )
+                                }
 
-                                BuffRemove::Single | BuffRemove::Unknown(_) => {}
+                                BuffRemove::Unknown(_) => {}
                             }
                         }
                     }
@@ -109,9 +129,28 @@ impl Plugin {
                 if statechange != StateChange::BuffInitial {
                     let mut guard = Self::lock();
                     let plugin = guard.as_mut();
-                    plugin
-                        .reminder
-                        .update_pending_check(&plugin.tracker.players, event.time);
+                    if let Some((target_id, start_time)) = plugin.reminder.update_pending_check(
+                        &plugin.tracker.players,
+                        plugin
+                            .defs
+                            .categories()
+                            .map(|category| category.name.as_ref()),
+                        event.time,
+                    ) {
+                        let custom = plugin.reminder.applicable_custom();
+                        let pull = plugin.pulls.record(
+                            target_id,
+                            start_time,
+                            &plugin.tracker.players,
+                            custom,
+                        );
+                        debug!(
+                            "Recorded pull {} for id {} with {} players",
+                            pull.number,
+                            target_id,
+                            pull.players.len()
+                        );
+                    }
                 }
             } else {
                 // check for player tracking change
@@ -143,76 +182,84 @@ impl Plugin {
         event_id: u64,
     ) {
         let statechange = event.get_statechange();
+        let encounter = self.reminder.current_encounter().is_some();
         if let Some(Entry { player, data }) = self.tracker.players.player_mut(player_id) {
             if let Some(remind) = self.reminder.custom(buff_id) {
+                // remember ingame names for unnamed reminders
+                if let Some(name) = buff_name.filter(|_| remind.name.is_empty()) {
+                    self.skill_names.record(buff_id, name);
+                }
+
                 debug!(
                     "Custom {} apply id {} time {} statechange {}",
-                    remind.display_name(),
+                    remind.resolved_name(&self.skill_names),
                     event_id,
                     event.time,
                     statechange
                 );
-                if data.apply_custom(buff_id, event.time) {
+                if data.apply_custom(buff_id, event.time, statechange == StateChange::BuffInitial) {
+                    data.record(HistoryBuff::Custom(buff_id), event.time, encounter);
                     info!(
-                        "{} ({}) applied to {}",
-                        remind.display_name(),
+                        "{} ({}) applied to {} ({:?})",
+                        remind.resolved_name(&self.skill_names),
                         buff_id,
-                        player.character
+                        player.character,
+                        data.custom_state(buff_id)
                     );
                 }
             } else {
-                match self.defs.buff_kind(buff_id, buff_name) {
-                    BuffKind::Food(food) => {
-                        debug!(
-                            "Food apply id {} time {} statechange {}",
-                            event_id, event.time, statechange
-                        );
-                        if data.apply_food(buff_id, event.time) {
-                            if let Some(food) = food {
-                                info!(
-                                    "Food {} ({}) applied to {}",
-                                    food.name, food.id, player.character
-                                );
-                            } else {
-                                info!("Unknown Food {} applied to {}", buff_id, player.character);
-                            }
-
-                            if player.is_self {
-                                self.reminder.self_buff_apply(buff_id);
-                            }
+                // track aliases as the buff they belong to
+                let buff_id = self.defs.resolve_id(buff_id);
+                let kind = self.defs.buff_kind(buff_id, buff_name);
+                if let Some((slot, buff)) = kind.slot() {
+                    let name = slot.name();
+                    debug!(
+                        "{} apply id {} time {} statechange {}",
+                        name, event_id, event.time, statechange
+                    );
+                    let initial = statechange == StateChange::BuffInitial;
+                    if data.apply(slot, buff_id, event.time, initial) {
+                        data.record(slot.into(), event.time, encounter);
+                        if let Some(buff) = buff {
+                            info!(
+                                "{} {} ({}) applied to {}",
+                                name, buff.name, buff.id, player.character
+                            );
+                        } else {
+                            info!(
+                                "Unknown {} {} applied to {}",
+                                name, buff_id, player.character
+                            );
 
-                            self.tracker.refresh_sort_if(Sorting::Food);
-                        }
-                    }
-                    BuffKind::Util(util) => {
-                        debug!(
-                            "Util apply id {} time {} statechange {}",
-                            event_id, event.time, statechange
-                        );
-                        if data.apply_util(buff_id, event.time) {
-                            if let Some(util) = util {
-                                info!(
-                                    "Utility {} ({}) applied to {}",
-                                    util.name, util.id, player.character
-                                );
-                            } else {
-                                info!(
-                                    "Unknown Utility {} applied to {}",
-                                    buff_id, player.character
-                                );
+                            // remember ingame names for unknown consumables
+                            if let Some(name) = buff_name {
+                                self.skill_names.record(buff_id, name);
                             }
-
-                            if player.is_self {
-                                self.reminder.self_buff_apply(buff_id);
+                            if let Some(unknown) = UnknownKind::from_slot(slot) {
+                                if self.collector.record(
+                                    buff_id,
+                                    unknown,
+                                    buff_name,
+                                    &player.character,
+                                ) {
+                                    info!("Collected unknown {} {}", name, buff_id);
+                                }
                             }
+                        }
 
-                            self.tracker.refresh_sort_if(Sorting::Util);
+                        if player.is_self {
+                            self.reminder.self_buff_apply(buff_id);
                         }
+
+                        self.tracker.refresh_sort_if_slot(slot);
                     }
-                    BuffKind::Ignore => {
-                        info!("Ignored buff {} applied to {}", buff_id, player.character);
-                    }
-                    BuffKind::Unknown => {}
+                } else if let BuffKind::Ignore(reason) = kind {
+                    info!(
+                        "Ignored buff {} applied to {}{}",
+                        buff_id,
+                        player.character,
+                        ignore_reason(reason)
+                    );
                 }
             }
         }
@@ -228,23 +275,38 @@ impl Plugin {
         event_id: u64,
     ) {
         let statechange = event.get_statechange();
+        let encounter = self.reminder.current_encounter().is_some();
         let buffremove = event.get_buffremove();
+        let single = matches!(buffremove, BuffRemove::Single);
         if let Some(Entry { player, data }) = self.tracker.players.player_mut(player_id) {
             if let Some(remind) = self.reminder.custom(buff_id) {
+                // remember ingame names for unnamed reminders
+                if let Some(name) = buff_name.filter(|_| remind.name.is_empty()) {
+                    self.skill_names.record(buff_id, name);
+                }
+
                 debug!(
                     "Custom {} remove id {} time {} statechange {} kind {}",
-                    remind.display_name(),
+                    remind.resolved_name(&self.skill_names),
                     event_id,
                     event.time,
                     statechange,
                     buffremove
                 );
-                if data.remove_custom(buff_id, event.time) {
+                let changed = if single {
+                    data.remove_custom_stack(buff_id, event.time)
+                } else {
+                    data.remove_custom(buff_id, event.time)
+                };
+                if changed {
+                    data.record(HistoryBuff::Custom(buff_id), event.time, encounter);
                     info!(
-                        "{} ({}) removed from {}",
-                        remind.display_name(),
+                        "{} ({}) {} from {} ({:?})",
+                        remind.resolved_name(&self.skill_names),
                         buff_id,
-                        player.character
+                        if single { "stack removed" } else { "removed" },
+                        player.character,
+                        data.custom_state(buff_id)
                     );
 
                     // check for custom buff running out
@@ -252,61 +314,45 @@ impl Plugin {
                         self.reminder.self_custom_remove(data);
                     }
                 }
-            } else {
-                match self.defs.buff_kind(buff_id, buff_name) {
-                    BuffKind::Food(food) => {
-                        debug!(
-                            "Food remove id {} time {} statechange {} kind {}",
-                            event_id, event.time, statechange, buffremove
-                        );
-                        if data.remove_food(buff_id, event.time) {
-                            if let Some(food) = food {
-                                info!(
-                                    "Food {} ({}) removed from {}",
-                                    food.name, food.id, player.character
-                                );
-                            } else {
-                                info!("Unknown Food {} removed from {}", buff_id, player.character);
-                            }
-
-                            // check for food running out
-                            if player.is_self {
-                                self.reminder.self_food_remove(data);
-                            }
-
-                            self.tracker.refresh_sort_if(Sorting::Food);
+            } else if !single {
+                // consumables do not stack, only track full removes
+                // aliases are tracked as the buff they belong to
+                let buff_id = self.defs.resolve_id(buff_id);
+                let kind = self.defs.buff_kind(buff_id, buff_name);
+                if let Some((slot, buff)) = kind.slot() {
+                    let name = slot.name();
+                    debug!(
+                        "{} remove id {} time {} statechange {} kind {}",
+                        name, event_id, event.time, statechange, buffremove
+                    );
+                    if data.remove(slot, buff_id, event.time) {
+                        data.record(slot.into(), event.time, encounter);
+                        if let Some(buff) = buff {
+                            info!(
+                                "{} {} ({}) removed from {}",
+                                name, buff.name, buff.id, player.character
+                            );
+                        } else {
+                            info!(
+                                "Unknown {} {} removed from {}",
+                                name, buff_id, player.character
+                            );
                         }
-                    }
-                    BuffKind::Util(util) => {
-                        debug!(
-                            "Utility remove id {} time {} statechange {} kind {}",
-                            event_id, event.time, statechange, buffremove
-                        );
-                        if data.remove_util(buff_id, event.time) {
-                            if let Some(util) = util {
-                                info!(
-                                    "Utility {} ({}) removed from {}",
-                                    util.name, util.id, player.character
-                                );
-                            } else {
-                                info!(
-                                    "Unknown Utility {} removed from {}",
-                                    buff_id, player.character
-                                );
-                            }
 
-                            // check for utility running out
-                            if player.is_self {
-                                self.reminder.self_util_remove(data);
-                            }
-
-                            self.tracker.refresh_sort_if(Sorting::Util);
+                        // check for consumable running out
+                        if player.is_self {
+                            self.reminder.self_slot_remove(slot, data);
                         }
+
+                        self.tracker.refresh_sort_if_slot(slot);
                     }
-                    BuffKind::Ignore => {
-                        info!("Ignored buff {} removed from {}", buff_id, player.character)
-                    }
-                    BuffKind::Unknown => {}
+                } else if let BuffKind::Ignore(reason) = kind {
+                    info!(
+                        "Ignored buff {} removed from {}{}",
+                        buff_id,
+                        player.character,
+                        ignore_reason(reason)
+                    )
                 }
             }
         }
@@ -321,6 +367,13 @@ impl Plugin {
         };
     }
 
+    /// Handles a client language change from unofficial extras.
+    pub fn extras_language_changed(&mut self, language: Language) {
+        self.extras_language = Some(language.into());
+        self.defs.set_language(self.client_language());
+        info!("Client language changed to {:?}", self.extras_language);
+    }
+
     /// Handles a squad update from unofficial extras.
     pub fn extras_squad_update(&mut self, users: UserInfoIter) {
         for user in users {
@@ -350,3 +403,10 @@ impl Plugin {
         self.tracker.refresh_sort_if(Sorting::Sub);
     }
 }
+
+/// Formats the reason for an ignored buff as log suffix.
+fn ignore_reason(reason: Option<&str>) -> String {
+    reason
+        .map(|reason| format!(" ({reason})"))
+        .unwrap_or_default()
+}
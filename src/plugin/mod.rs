@@ -1,10 +1,18 @@
 pub mod event;
 pub mod ui;
+mod watch;
 
 use crate::{
-    data::{Definitions, LoadError},
+    collector::Collector,
+    data::{
+        DefData, DefinitionSource, Definitions, DefinitionsPack, EntryError, Language, LoadError,
+        SkillNames, StaleOverride, DEFAULTS,
+    },
+    editor::Editor,
+    pulls::PullHistory,
     reminder::Reminder,
     tracking::Tracker,
+    util::has_jsonc_comments,
 };
 use arc_util::{
     settings::Settings,
@@ -14,7 +22,14 @@ use log::{info, warn};
 use once_cell::sync::Lazy;
 use semver::Version;
 use std::sync::Mutex;
-use std::{fs, sync::MutexGuard};
+use std::{
+    collections::BTreeSet,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::MutexGuard,
+    time::{Duration, Instant},
+};
+use watch::FileWatcher;
 
 #[cfg(feature = "demo")]
 use crate::demo::Demo;
@@ -28,6 +43,18 @@ const SETTINGS_FILE: &str = "arcdps_food_reminder.json";
 /// Definitions file name.
 const DEFINITIONS_FILE: &str = "arcdps_food_reminder_definitions.json";
 
+/// Learned definitions file name.
+const LEARNED_FILE: &str = "arcdps_food_reminder_learned.json";
+
+/// Exported definitions file name.
+const EXPORT_FILE: &str = "arcdps_food_reminder_export.csv";
+
+/// Definitions packs directory name.
+const DEFINITIONS_DIR: &str = "food_reminder_definitions";
+
+/// Interval between checks for changed definitions files.
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
 /// Main plugin instance.
 // FIXME: a single mutex for the whole thing is potentially inefficient
 static PLUGIN: Lazy<Mutex<Plugin>> = Lazy::new(|| Mutex::new(Plugin::new()));
@@ -38,11 +65,53 @@ pub struct Plugin {
     /// State of unofficial extras.
     extras: ExtrasState,
 
+    /// Client language reported by unofficial extras.
+    extras_language: Option<Language>,
+
+    /// Client language set by the user.
+    ///
+    /// [`None`] uses the language from unofficial extras.
+    language: Option<Language>,
+
     /// Definitions.
     defs: Definitions,
 
     /// State of loading custom definitions.
-    defs_state: Result<(), LoadError>,
+    ///
+    /// Contains the skipped entries if loaded.
+    defs_state: Result<Vec<EntryError>, LoadError>,
+
+    /// State of loading learned definitions.
+    ///
+    /// Contains the skipped entries if loaded.
+    learned_state: Result<Vec<EntryError>, LoadError>,
+
+    /// Custom definitions overriding defaults without need.
+    stale: Vec<StaleOverride>,
+
+    /// Confirmation for stale definitions cleanup.
+    cleanup_confirm: bool,
+
+    /// Whether to reload definitions when the files change.
+    auto_reload: bool,
+
+    /// Watcher for changes to definitions files.
+    watcher: FileWatcher,
+
+    /// Time & success of the last automatic reload.
+    last_reload: Option<(Instant, bool)>,
+
+    /// Result of the last definitions export.
+    last_export: Option<Result<PathBuf, String>>,
+
+    /// Definitions packs from the definitions directory.
+    packs: Vec<DefinitionsPack>,
+
+    /// File names of disabled definitions packs.
+    disabled_packs: BTreeSet<String>,
+
+    /// Ingame skill names seen in combat events.
+    skill_names: SkillNames,
 
     /// Food reminder.
     reminder: Reminder,
@@ -50,6 +119,15 @@ pub struct Plugin {
     /// Food tracker window.
     tracker: Window<Tracker>,
 
+    /// Unknown buff collector window.
+    collector: Window<Collector>,
+
+    /// Pull history window.
+    pulls: Window<PullHistory>,
+
+    /// Custom definitions editor.
+    editor: Editor,
+
     /// Demo window.
     #[cfg(feature = "demo")]
     demo: Window<Demo>,
@@ -63,8 +141,20 @@ impl Plugin {
     pub fn new() -> Self {
         Self {
             extras: ExtrasState::Missing,
+            extras_language: None,
+            language: None,
             defs: Definitions::with_defaults(),
             defs_state: Err(LoadError::NotFound),
+            learned_state: Err(LoadError::NotFound),
+            stale: Vec::new(),
+            cleanup_confirm: false,
+            auto_reload: true,
+            watcher: FileWatcher::new(WATCH_INTERVAL),
+            last_reload: None,
+            last_export: None,
+            packs: Vec::new(),
+            disabled_packs: BTreeSet::new(),
+            skill_names: SkillNames::default(),
             reminder: Reminder::new(),
 
             tracker: Window::new(
@@ -76,6 +166,26 @@ impl Plugin {
                 },
             ),
 
+            collector: Window::new(
+                "Unknown Buffs",
+                Collector::new(),
+                WindowOptions {
+                    auto_resize: true,
+                    ..Default::default()
+                },
+            ),
+
+            pulls: Window::new(
+                "Pull History",
+                PullHistory::new(),
+                WindowOptions {
+                    auto_resize: true,
+                    ..Default::default()
+                },
+            ),
+
+            editor: Editor::new(),
+
             #[cfg(feature = "demo")]
             demo: Window::new(
                 "Food Demo",
@@ -125,6 +235,8 @@ impl Plugin {
         // load component settings
         settings.load_component(&mut self.tracker);
         settings.load_component(&mut self.reminder);
+        settings.load_component(&mut self.collector);
+        settings.load_component(&mut self.pulls);
 
         #[cfg(feature = "demo")]
         {
@@ -132,31 +244,330 @@ impl Plugin {
             self.refresh_demo_settings();
         }
 
-        // load custom defs
-        if let Some(defs_path) = Settings::config_path(DEFINITIONS_FILE) {
-            const DEFAULTS_CHANGE: Version = Version::new(0, 4, 0);
+        // load client language
+        self.language = settings.load_data("language").flatten();
 
-            // check for minimum version
-            if matches!(settings_version, Some(version) if version >= DEFAULTS_CHANGE) {
-                if defs_path.exists() {
-                    // try loading custom defs
-                    self.defs_state = self.defs.try_load(&defs_path);
+        // load defs packs & custom defs
+        if let Some(auto_reload) = settings.load_data("auto_reload") {
+            self.auto_reload = auto_reload;
+        }
+        if let Some(disabled) = settings.load_data("disabled_packs") {
+            self.disabled_packs = disabled;
+        }
+        self.reload_defs();
+    }
 
-                    if self.defs_state.is_ok() {
-                        info!("Loaded custom definitions from \"{}\"", defs_path.display());
-                    } else {
-                        warn!(
-                            "Failed to load custom definitions from \"{}\"",
-                            defs_path.display()
-                        );
-                    }
+    /// Returns the current client language.
+    ///
+    /// Prefers the language set by the user over the language from unofficial extras.
+    pub fn client_language(&self) -> Option<Language> {
+        self.language.or(self.extras_language)
+    }
+
+    /// Reloads all definitions.
+    ///
+    /// Applies the defaults, followed by the learned definitions, enabled packs in order and finally the custom definitions file.
+    pub fn reload_defs(&mut self) {
+        let loaded = LoadedDefs::load(self.client_language(), &self.disabled_packs);
+        self.apply_defs(loaded);
+
+        // remember file states for automatic reload
+        self.watcher.reset(Self::defs_paths());
+    }
+
+    /// Replaces the current definitions & load states with freshly loaded definitions.
+    fn apply_defs(&mut self, loaded: LoadedDefs) {
+        self.defs = loaded.defs;
+        self.learned_state = loaded.learned_state;
+        self.packs = loaded.packs;
+        self.defs_state = loaded.defs_state;
+        self.stale = loaded.stale;
+
+        // drop collected buffs which are known now
+        self.collector.remove_known(&self.defs);
+
+        // refresh editor unless there are unsaved changes
+        if !self.editor.is_dirty() {
+            self.editor.unload();
+        }
+    }
+
+    /// Upgrades a definitions file from an older format version in place.
+    ///
+    /// Only files changed by the migration are rewritten, keeping the original file as backup.
+    /// Files with invalid entries are not rewritten.
+    fn upgrade_defs_file(path: &Path) {
+        let Ok((data, errors, migration)) = DefData::load_file_migrated(path) else {
+            return;
+        };
+
+        if migration.is_newer() {
+            warn!("Definitions \"{}\" {migration}", path.display());
+            return;
+        }
+        if !migration.upgraded() || migration.changes.is_empty() {
+            return;
+        }
+        if !errors.is_empty() {
+            warn!(
+                "Not upgrading definitions \"{}\", file contains invalid entries",
+                path.display()
+            );
+            return;
+        }
+
+        let backup = path.with_extension(format!("v{}.bak", migration.from));
+        if let Err(err) = fs::copy(path, &backup) {
+            warn!(
+                "Not upgrading definitions \"{}\", failed to create backup: {err}",
+                path.display()
+            );
+            return;
+        }
+        match fs::write(path, data.to_json_pretty()) {
+            Ok(()) => info!(
+                "Definitions \"{}\" {migration}, backup saved to \"{}\"",
+                path.display(),
+                backup.display()
+            ),
+            Err(err) => warn!(
+                "Failed to upgrade definitions \"{}\": {err}",
+                path.display()
+            ),
+        }
+    }
+
+    /// Reloads the definitions if the definitions files changed.
+    ///
+    /// Keeps the previous definitions if a changed or previously loaded file fails to load.
+    /// Unchanged files which failed to load before do not prevent the reload.
+    ///
+    /// Files are checked & loaded without holding the plugin lock.
+    pub fn check_defs_changes() {
+        let (mut watcher, language, disabled_packs, failed) = {
+            let mut plugin = Self::lock();
+            if !plugin.auto_reload || !plugin.watcher.due() {
+                return;
+            }
+            (
+                plugin.watcher.clone(),
+                plugin.client_language(),
+                plugin.disabled_packs.clone(),
+                failed_paths(&plugin.learned_state, &plugin.defs_state, &plugin.packs),
+            )
+        };
+
+        let paths = Self::defs_paths();
+        if !watcher.changed(paths.clone()) {
+            return;
+        }
+        info!("Definitions files changed, reloading");
+
+        // failures of unchanged files are known already
+        let known = failed
+            .into_iter()
+            .filter(|path| !watcher.file_changed(path))
+            .collect::<Vec<_>>();
+
+        let loaded = LoadedDefs::load(language, &disabled_packs);
+        let success = loaded
+            .failed_paths()
+            .iter()
+            .all(|path| known.contains(path));
+        watcher.reset(paths);
+
+        let mut plugin = Self::lock();
+        if success {
+            plugin.apply_defs(loaded);
+        } else {
+            warn!("Failed to reload changed definitions, keeping previous definitions");
+        }
+        plugin.watcher = watcher;
+        plugin.last_reload = Some((Instant::now(), success));
+    }
+
+    /// Returns the paths of all definitions files.
+    fn defs_paths() -> Vec<PathBuf> {
+        let packs = Settings::config_path(DEFINITIONS_DIR)
+            .and_then(|dir| DefinitionsPack::find_all(dir).ok())
+            .into_iter()
+            .flatten()
+            .map(|pack| pack.path);
+
+        [LEARNED_FILE, DEFINITIONS_FILE]
+            .into_iter()
+            .filter_map(|file| Settings::config_path(file))
+            .chain(packs)
+            .collect()
+    }
+
+    /// Loads the custom definitions file into the editor.
+    ///
+    /// Files with invalid entries are not editable to avoid losing them on save.
+    pub fn load_editor(&mut self) {
+        let data = match Settings::config_path(DEFINITIONS_FILE) {
+            Some(defs_path) => match DefData::load_file(defs_path) {
+                Ok((data, errors)) if errors.is_empty() => Ok(data),
+                Ok((_, errors)) => Err(format!(
+                    "Custom definitions contain {} invalid entries",
+                    errors.len()
+                )),
+                Err(LoadError::NotFound) => Ok(DefData::default()),
+                Err(err) => Err(err.to_string()),
+            },
+            None => Err("Unable to locate config directory".into()),
+        };
+        let comments = data.is_ok()
+            && Settings::config_path(DEFINITIONS_FILE)
+                .and_then(|path| fs::read_to_string(path).ok())
+                .is_some_and(|content| has_jsonc_comments(&content));
+        self.editor.load(data, comments);
+    }
+
+    /// Removes stale overrides from the custom definitions file.
+    pub fn cleanup_stale(&mut self) {
+        let Some(defs_path) = Settings::config_path(DEFINITIONS_FILE) else {
+            return;
+        };
+
+        // never rewrite a file with invalid entries
+        match DefData::load_file(defs_path) {
+            Ok((mut data, errors)) if errors.is_empty() => {
+                for stale in &self.stale {
+                    data.remove(stale.id);
                 }
-            } else {
-                // settings are from old version, remove old defs file
-                let _ = fs::remove_file(defs_path);
+                info!("Removing {} stale custom definitions", self.stale.len());
+                self.save_custom_defs(data);
+            }
+            Ok(_) => warn!("Not cleaning up custom definitions, file contains invalid entries"),
+            Err(err) => warn!("Not cleaning up custom definitions: {err}"),
+        }
+    }
+
+    /// Saves definitions data into the custom definitions file and reloads the definitions.
+    ///
+    /// A file containing comments is kept as backup, since comments are not preserved.
+    pub fn save_custom_defs(&mut self, data: DefData) {
+        let Some(defs_path) = Settings::config_path(DEFINITIONS_FILE) else {
+            return;
+        };
+
+        let comments =
+            fs::read_to_string(&defs_path).is_ok_and(|content| has_jsonc_comments(&content));
+        if comments {
+            let backup = defs_path.with_extension("json.bak");
+            match fs::copy(&defs_path, &backup) {
+                Ok(_) => info!(
+                    "Custom definitions contain comments, backup saved to \"{}\"",
+                    backup.display()
+                ),
+                Err(err) => {
+                    warn!("Not saving custom definitions, failed to create backup: {err}");
+                    return;
+                }
+            }
+        }
+
+        match fs::write(&defs_path, data.to_json_pretty()) {
+            Ok(()) => {
+                info!("Saved custom definitions to \"{}\"", defs_path.display());
+                self.editor.saved();
+                self.reload_defs();
+            }
+            Err(err) => warn!(
+                "Failed to save custom definitions to \"{}\": {err}",
+                defs_path.display()
+            ),
+        }
+    }
 
-                info!("Removed definitions from old version");
+    /// Saves definitions data into the learned definitions file and reloads the definitions.
+    ///
+    /// Entries already present in the file are replaced.
+    pub fn save_learned(&mut self, data: DefData) {
+        let Some(learned_path) = Settings::config_path(LEARNED_FILE) else {
+            return;
+        };
+
+        // keep existing learned definitions, never overwrite a broken file
+        let mut learned = match DefData::load_file(&learned_path) {
+            Ok((learned, errors)) if errors.is_empty() => learned,
+            Err(LoadError::NotFound) => DefData::default(),
+            Ok(_) => {
+                warn!("Not saving learned definitions, file contains invalid entries");
+                return;
             }
+            Err(err) => {
+                warn!("Not saving learned definitions: {err}");
+                return;
+            }
+        };
+
+        let count = data.len();
+        learned.merge(data);
+        match fs::write(&learned_path, learned.to_json_pretty()) {
+            Ok(()) => info!(
+                "Saved {count} learned definitions to \"{}\"",
+                learned_path.display()
+            ),
+            Err(err) => warn!(
+                "Failed to save learned definitions to \"{}\": {err}",
+                learned_path.display()
+            ),
+        }
+
+        self.reload_defs();
+    }
+
+    /// Exports the current merged definitions as CSV.
+    pub fn export_csv(&mut self) {
+        let Some(export_path) = Settings::config_path(EXPORT_FILE) else {
+            return;
+        };
+
+        let result = fs::write(&export_path, self.defs.to_data().to_csv());
+        self.last_export = Some(match result {
+            Ok(()) => {
+                info!(
+                    "Exported {} definitions to \"{}\"",
+                    self.defs.len(),
+                    export_path.display()
+                );
+                Ok(export_path)
+            }
+            Err(err) => {
+                warn!(
+                    "Failed to export definitions to \"{}\": {err}",
+                    export_path.display()
+                );
+                Err(err.to_string())
+            }
+        });
+    }
+
+    /// Enables or disables a definitions pack and reloads the definitions.
+    pub fn set_pack_enabled(&mut self, name: &str, enabled: bool) {
+        if enabled {
+            self.disabled_packs.remove(name);
+        } else {
+            self.disabled_packs.insert(name.into());
+        }
+        self.reload_defs();
+    }
+
+    /// Logs the result of loading a definitions file.
+    fn log_defs_state(path: &Path, state: &Result<Vec<EntryError>, LoadError>) {
+        match state {
+            Ok(errors) => {
+                info!("Loaded custom definitions from \"{}\"", path.display());
+                for err in errors {
+                    warn!("Skipped custom definition {err}");
+                }
+            }
+            Err(err) => warn!(
+                "Failed to load custom definitions from \"{}\": {err}",
+                path.display()
+            ),
         }
     }
 
@@ -165,10 +576,15 @@ impl Plugin {
         let mut settings = Settings::from_file(SETTINGS_FILE);
 
         settings.store_data("version", VERSION);
+        settings.store_data("disabled_packs", &self.disabled_packs);
+        settings.store_data("auto_reload", self.auto_reload);
+        settings.store_data("language", self.language);
 
         // update component settings
         settings.store_component(&self.tracker);
         settings.store_component(&self.reminder);
+        settings.store_component(&self.collector);
+        settings.store_component(&self.pulls);
 
         #[cfg(feature = "demo")]
         settings.store_component(&self.demo);
@@ -198,3 +614,110 @@ pub enum ExtrasState {
     Incompatible,
     Found,
 }
+
+/// Freshly loaded definitions & load states, applied to the plugin as a whole.
+#[derive(Debug)]
+struct LoadedDefs {
+    defs: Definitions,
+    learned_state: Result<Vec<EntryError>, LoadError>,
+    packs: Vec<DefinitionsPack>,
+    defs_state: Result<Vec<EntryError>, LoadError>,
+    stale: Vec<StaleOverride>,
+}
+
+impl LoadedDefs {
+    /// Loads the definitions from all files without touching the current plugin state.
+    fn load(language: Option<Language>, disabled_packs: &BTreeSet<String>) -> Self {
+        let mut defs = Definitions::with_defaults();
+        defs.set_language(language);
+
+        // load learned definitions first to be overwritten by packs
+        let learned_state = match Settings::config_path(LEARNED_FILE) {
+            Some(learned_path) if learned_path.exists() => {
+                Plugin::upgrade_defs_file(&learned_path);
+                let state = defs.try_load(&learned_path, DefinitionSource::Learned);
+                Plugin::log_defs_state(&learned_path, &state);
+                state
+            }
+            _ => Err(LoadError::NotFound),
+        };
+
+        // load packs from definitions directory
+        let mut packs = match Settings::config_path(DEFINITIONS_DIR).map(DefinitionsPack::find_all)
+        {
+            Some(Ok(packs)) => packs,
+            Some(Err(err)) if err.kind() != io::ErrorKind::NotFound => {
+                warn!("Failed to read definitions directory: {err}");
+                Vec::new()
+            }
+            _ => Vec::new(),
+        };
+        for pack in &mut packs {
+            pack.enabled = !disabled_packs.contains(&pack.name);
+            if pack.enabled {
+                pack.state = defs.try_load(&pack.path, DefinitionSource::Pack);
+                Plugin::log_defs_state(&pack.path, &pack.state);
+            }
+        }
+
+        // load custom definitions file last to overwrite packs
+        let mut stale = Vec::new();
+        let defs_state = match Settings::config_path(DEFINITIONS_FILE) {
+            Some(defs_path) if defs_path.exists() => {
+                Plugin::upgrade_defs_file(&defs_path);
+                let state = match DefData::load_file(&defs_path) {
+                    Ok((data, errors)) => {
+                        // check for overrides of defaults without need
+                        stale = data.stale_overrides(&DEFAULTS);
+                        if !stale.is_empty() {
+                            info!("Found {} stale custom definitions", stale.len());
+                        }
+
+                        // report problems like long display names
+                        for lint in data.lint(false) {
+                            warn!("Definitions \"{}\": {lint}", defs_path.display());
+                        }
+
+                        defs.add_data(data, DefinitionSource::Custom);
+                        Ok(errors)
+                    }
+                    Err(err) => Err(err),
+                };
+                Plugin::log_defs_state(&defs_path, &state);
+                state
+            }
+            _ => Err(LoadError::NotFound),
+        };
+
+        Self {
+            defs,
+            learned_state,
+            packs,
+            defs_state,
+            stale,
+        }
+    }
+
+    /// Returns the paths of all present definitions files which failed to load.
+    fn failed_paths(&self) -> Vec<PathBuf> {
+        failed_paths(&self.learned_state, &self.defs_state, &self.packs)
+    }
+}
+
+/// Returns the paths of all present definitions files which failed to load.
+fn failed_paths(
+    learned_state: &Result<Vec<EntryError>, LoadError>,
+    defs_state: &Result<Vec<EntryError>, LoadError>,
+    packs: &[DefinitionsPack],
+) -> Vec<PathBuf> {
+    let failed = |state: &Result<Vec<EntryError>, LoadError>| matches!(state, Err(err) if *err != LoadError::NotFound);
+
+    let learned = Settings::config_path(LEARNED_FILE).filter(|_| failed(learned_state));
+    let custom = Settings::config_path(DEFINITIONS_FILE).filter(|_| failed(defs_state));
+    let packs = packs
+        .iter()
+        .filter(|pack| pack.enabled && failed(&pack.state))
+        .map(|pack| pack.path.clone());
+
+    learned.into_iter().chain(custom).chain(packs).collect()
+}
@@ -1,37 +1,55 @@
 use super::Plugin;
 use crate::{
-    combo_ui::render_enum_combo,
-    data::{Definitions, LoadError},
-    plugin::{ExtrasState, DEFINITIONS_FILE},
+    buff_ui,
+    combo_ui::{render_combo, render_enum_combo},
+    data::{DefinitionSource, Definitions, EntryError, Language, LoadError},
+    plugin::{ExtrasState, DEFINITIONS_DIR, DEFINITIONS_FILE, EXPORT_FILE, LEARNED_FILE},
     reminder::custom::CustomReminder,
+    tracking::buff::current_time,
 };
 use arc_util::{
-    colors::{GREEN, GREY, RED, YELLOW},
-    settings::{HasSettings, Settings},
+    colors::{Color, GREEN, GREY, RED, YELLOW},
+    settings::HasSettings,
     ui::{action::Action, render, Component, Hideable},
 };
 use arcdps::{
     exports::{self, CoreColor},
-    imgui::{InputTextFlags, Ui},
+    imgui::{InputTextFlags, TabBar, TabItem, Ui},
 };
-use log::{info, warn};
-use std::time::Duration;
+use std::{iter, time::Duration};
 
 impl Plugin {
     /// Callback for standalone UI creation.
     pub fn render_windows(&mut self, ui: &Ui, not_loading: bool) {
         // reminder, log & demo render always
-        self.reminder.render(ui, ());
+        self.reminder.render(ui, &self.skill_names);
 
         #[cfg(feature = "demo")]
-        self.demo
-            .render(ui, (&self.defs, self.reminder.all_custom()));
+        self.demo.render(
+            ui,
+            (&self.defs, self.reminder.all_custom(), &self.skill_names),
+        );
+
+        // predict expiry of tracked buffs
+        self.tracker.expire_buffs(&self.defs, current_time());
 
         // other ui renders conditionally
         let ui_settings = exports::ui_settings();
         if !ui_settings.hidden && (not_loading || ui_settings.draw_always) {
-            self.tracker
-                .render(ui, (&self.defs, self.reminder.all_custom()));
+            self.tracker.render(
+                ui,
+                (&self.defs, self.reminder.all_custom(), &self.skill_names),
+            );
+            self.collector.render(ui, ());
+            self.pulls.render(
+                ui,
+                (&self.defs, self.reminder.all_custom(), &self.skill_names),
+            );
+        }
+
+        // save learned definitions from collector
+        if let Some(learned) = self.collector.take_learned() {
+            self.save_learned(learned);
         }
     }
 
@@ -44,8 +62,6 @@ impl Plugin {
         let green = colors.core(CoreColor::LightGreen).unwrap_or(GREEN);
         let yellow = colors.core(CoreColor::LightYellow).unwrap_or(YELLOW);
 
-        const SPACING: f32 = 5.0;
-
         // use small padding
         let _style = render::small_padding(ui);
 
@@ -84,6 +100,29 @@ impl Plugin {
             );
         }
 
+        // client language
+        ui.set_next_item_width(input_width);
+        let language_changed = render_combo(
+            ui,
+            "Client language",
+            iter::once(None).chain(Language::ALL.map(Some)),
+            &mut self.language,
+            |language| match language {
+                Some(language) => language.as_ref().into(),
+                None => match self.extras_language {
+                    Some(extras) => format!("Auto ({})", extras.as_ref()).into(),
+                    None => "Auto".into(),
+                },
+            },
+            |_| None,
+        );
+        if language_changed {
+            self.defs.set_language(self.client_language());
+        }
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Used to detect unknown Food & Utility buffs and display names.\nAuto uses the language reported by unofficial extras.");
+        }
+
         // reset buttons
         self.tracker.render_reset_buttons(ui, true);
 
@@ -94,6 +133,12 @@ impl Plugin {
 
         ui.checkbox("Remind for Food buff", &mut self.reminder.settings.food);
         ui.checkbox("Remind for Utility buff", &mut self.reminder.settings.util);
+        for category in self.defs.categories() {
+            ui.checkbox(
+                format!("Remind for {} buff", category.name),
+                self.reminder.settings.category_mut(&category.name),
+            );
+        }
 
         ui.checkbox(
             "Remind on encounter start",
@@ -160,7 +205,11 @@ impl Plugin {
 
         // test button
         if ui.button("Test reminder") {
-            self.reminder.trigger_all();
+            self.reminder.trigger_all(
+                self.defs
+                    .categories()
+                    .map(|category| category.name.as_ref()),
+            );
         }
 
         ui.spacing();
@@ -188,7 +237,25 @@ impl Plugin {
             ui.same_line();
             ui.set_next_item_width(input_width);
             ui.input_text(format!("##custom-name-{i}"), &mut remind.name)
+                .hint(self.skill_names.get(remind.id).unwrap_or_default())
                 .build();
+            if remind.name.is_empty() && ui.is_item_hovered() {
+                ui.tooltip_text("Empty names use the ingame skill name once seen.");
+            }
+
+            let mut stacks = remind.min_stacks.max(1).try_into().unwrap_or(1);
+            ui.same_line();
+            ui.set_next_item_width(render::ch_width(ui, 4));
+            if ui
+                .input_int(format!("##custom-stacks-{i}"), &mut stacks)
+                .step(0)
+                .build()
+            {
+                remind.min_stacks = stacks.max(1) as u32;
+            }
+            if ui.is_item_hovered() {
+                ui.tooltip_text("Minimum stacks required.");
+            }
 
             ui.same_line();
             ui.set_next_item_width(70.0);
@@ -210,52 +277,179 @@ impl Plugin {
         ui.spacing();
 
         ui.text_colored(grey, "Custom definitions");
+        TabBar::new("##defs-tabs").build(ui, || {
+            TabItem::new("Status").build(ui, || {
+                self.render_defs_status(ui);
+            });
+
+            TabItem::new("Editor").build(ui, || {
+                self.render_defs_editor(ui);
+            });
+        });
+
+        ui.spacing();
+        ui.separator();
+        ui.spacing();
+
+        // reset button
+        if render::reset_button(ui, "Reset to default", &mut self.reset_confirm) {
+            self.tracker.reset_settings();
+            self.reminder.reset_settings();
+        }
+
+        #[cfg(feature = "demo")]
+        self.refresh_demo_settings();
+    }
+
+    /// Renders the status of definitions packs & custom definitions.
+    fn render_defs_status(&mut self, ui: &Ui) {
+        let colors = exports::colors();
+        let grey = colors.core(CoreColor::MediumGrey).unwrap_or(GREY);
+        let red = colors.core(CoreColor::LightRed).unwrap_or(RED);
+        let green = colors.core(CoreColor::LightGreen).unwrap_or(GREEN);
+        let yellow = colors.core(CoreColor::LightYellow).unwrap_or(YELLOW);
+
+        const SPACING: f32 = 5.0;
+
         ui.text("Status:");
         ui.same_line();
-        match self.defs_state {
-            Ok(()) => ui.text_colored(green, "Loaded"),
-            Err(LoadError::NotFound) => ui.text_colored(yellow, "Not found"),
-            Err(LoadError::FailedToRead) => ui.text_colored(red, "Failed to read file"),
-            Err(LoadError::InvalidJSON) => ui.text_colored(red, "Failed to parse JSON"),
-        }
-
-        if ui.button("Reload definitions file") {
-            if let Some(defs_path) = Settings::config_path(DEFINITIONS_FILE) {
-                // try loading custom defs
-                self.defs_state = self.defs.try_load(&defs_path);
-
-                if self.defs_state.is_ok() {
-                    info!(
-                        "Reloaded custom definitions from \"{}\"",
-                        defs_path.display()
-                    );
+        render_defs_state(ui, &self.defs_state, red, green, yellow);
+
+        ui.text("Learned:");
+        ui.same_line();
+        render_defs_state(ui, &self.learned_state, red, green, yellow);
+        if ui.is_item_hovered() {
+            ui.tooltip_text(format!(
+                "Definitions saved from the Unknown Buffs window into \"{LEARNED_FILE}\"."
+            ));
+        }
+
+        // definitions packs
+        if !self.packs.is_empty() {
+            ui.spacing();
+            ui.text("Packs:");
+
+            let mut toggled = None;
+            for pack in &self.packs {
+                let mut enabled = pack.enabled;
+                if ui.checkbox(&pack.name, &mut enabled) {
+                    toggled = Some((pack.name.clone(), enabled));
+                }
+                if ui.is_item_hovered() {
+                    ui.tooltip_text(pack.path.display().to_string());
+                }
+
+                ui.same_line();
+                if pack.enabled {
+                    render_defs_state(ui, &pack.state, red, green, yellow);
                 } else {
-                    warn!(
-                        "Failed to reload custom definitions from \"{}\"",
-                        defs_path.display()
-                    );
+                    ui.text_colored(grey, "Disabled");
                 }
             }
+
+            if let Some((name, enabled)) = toggled {
+                self.set_pack_enabled(&name, enabled);
+            }
+        }
+
+        // entries per source
+        ui.spacing();
+        ui.text("Entries:");
+        for source in DefinitionSource::ALL {
+            ui.same_line();
+            ui.text(format!(
+                "{} {}",
+                self.defs.count_source(source),
+                source.as_ref()
+            ));
+        }
+
+        // stale custom overrides
+        if !self.stale.is_empty() {
+            ui.text_colored(yellow, format!("{} stale custom entries", self.stale.len()));
+            if ui.is_item_hovered() {
+                ui.tooltip(|| {
+                    for stale in &self.stale {
+                        ui.text(format!(
+                            "{} ({}): {}",
+                            stale.name,
+                            stale.id,
+                            stale.reason.as_ref()
+                        ));
+                    }
+                });
+            }
+            ui.same_line_with_spacing(0.0, SPACING);
+            if render::reset_button(ui, "Clean up", &mut self.cleanup_confirm) {
+                self.cleanup_stale();
+            }
+            if !self.cleanup_confirm && ui.is_item_hovered() {
+                ui.tooltip_text(format!(
+                    "Remove custom entries identical to their default definition from the \"{DEFINITIONS_FILE}\" file."
+                ));
+            }
+        }
+
+        ui.checkbox("Reload on file changes", &mut self.auto_reload);
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Automatically reload definitions when a definitions file changes.\nKeeps the previous definitions if a changed file fails to load.\nUnchanged files which failed to load before are ignored.");
+        }
+        if let Some((time, success)) = self.last_reload {
+            let ago = buff_ui::format_remaining(time.elapsed().as_millis() as u64);
+            if success {
+                ui.text_colored(green, format!("Reloaded changed files {ago} ago"));
+            } else {
+                ui.text_colored(
+                    red,
+                    format!("Failed to reload changed files {ago} ago, kept previous definitions"),
+                );
+            }
+        }
+
+        if ui.button("Reload definitions") {
+            self.reload_defs();
+        }
+        if ui.is_item_hovered() {
+            ui.tooltip_text(format!(
+                "Reload the definitions packs in \"{DEFINITIONS_DIR}\" & the \"{DEFINITIONS_FILE}\" file."
+            ));
         }
 
         ui.same_line_with_spacing(0.0, SPACING);
         if ui.button("Reset definitions") {
             self.defs = Definitions::with_defaults();
+            self.defs.set_language(self.client_language());
             self.defs_state = Err(LoadError::NotFound);
+            self.learned_state = Err(LoadError::NotFound);
+            self.stale.clear();
+            self.packs.clear();
         }
 
-        ui.spacing();
-        ui.separator();
-        ui.spacing();
-
-        // reset button
-        if render::reset_button(ui, "Reset to default", &mut self.reset_confirm) {
-            self.tracker.reset_settings();
-            self.reminder.reset_settings();
+        ui.same_line_with_spacing(0.0, SPACING);
+        if ui.button("Export CSV") {
+            self.export_csv();
+        }
+        if ui.is_item_hovered() {
+            ui.tooltip_text(format!(
+                "Export all current definitions to the \"{EXPORT_FILE}\" file.\nLocalized names are not included."
+            ));
+        }
+        match &self.last_export {
+            Some(Ok(path)) => ui.text_colored(green, format!("Exported to \"{}\"", path.display())),
+            Some(Err(err)) => ui.text_colored(red, format!("Failed to export: {err}")),
+            None => {}
         }
+    }
 
-        #[cfg(feature = "demo")]
-        self.refresh_demo_settings();
+    /// Renders the editor for custom definitions.
+    fn render_defs_editor(&mut self, ui: &Ui) {
+        if !self.editor.is_loaded() {
+            self.load_editor();
+        }
+        self.editor.render(ui, &self.defs);
+        if let Some(data) = self.editor.take_save() {
+            self.save_custom_defs(data);
+        }
     }
 
     /// Callback for ArcDPS option checkboxes.
@@ -263,6 +457,8 @@ impl Plugin {
         if option_name.is_none() {
             let mut plugin = Self::lock();
             ui.checkbox("Food Tracker", plugin.tracker.visible_mut());
+            ui.checkbox("Unknown Buffs", plugin.collector.visible_mut());
+            ui.checkbox("Pull History", plugin.pulls.visible_mut());
 
             #[cfg(feature = "demo")]
             ui.checkbox("Food Demo", plugin.demo.visible_mut());
@@ -284,3 +480,35 @@ impl Plugin {
         true
     }
 }
+
+/// Renders the state of loading a definitions file.
+fn render_defs_state(
+    ui: &Ui,
+    state: &Result<Vec<EntryError>, LoadError>,
+    red: Color,
+    green: Color,
+    yellow: Color,
+) {
+    match state {
+        Ok(errors) if errors.is_empty() => ui.text_colored(green, "Loaded"),
+        Ok(errors) => {
+            ui.text_colored(
+                yellow,
+                format!("Loaded, skipped {} invalid entries", errors.len()),
+            );
+            for err in errors {
+                ui.text_colored(yellow, err.to_string());
+            }
+        }
+        Err(LoadError::NotFound) => ui.text_colored(yellow, "Not found"),
+        Err(LoadError::FailedToRead) => ui.text_colored(red, "Failed to read file"),
+        Err(LoadError::InvalidJSON(err)) => {
+            ui.text_colored(red, "Failed to parse JSON");
+            ui.text_colored(red, err.to_string());
+        }
+        Err(LoadError::InvalidCSV(err)) => {
+            ui.text_colored(red, "Failed to parse CSV");
+            ui.text_colored(red, err);
+        }
+    }
+}
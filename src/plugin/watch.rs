@@ -0,0 +1,77 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+/// Watcher for file changes based on modification times.
+#[derive(Debug, Clone)]
+pub struct FileWatcher {
+    /// Interval between checks.
+    interval: Duration,
+
+    /// Time of the next check.
+    next_check: Option<Instant>,
+
+    /// Last known modification times of the watched files.
+    times: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl FileWatcher {
+    /// Creates a new file watcher checking in the given interval.
+    pub const fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            next_check: None,
+            times: Vec::new(),
+        }
+    }
+
+    /// Returns whether a check is due and schedules the next check.
+    pub fn due(&mut self) -> bool {
+        let now = Instant::now();
+        match self.next_check {
+            Some(next) if now < next => false,
+            _ => {
+                self.next_check = Some(now + self.interval);
+                true
+            }
+        }
+    }
+
+    /// Remembers the current modification times of the given files.
+    pub fn reset(&mut self, paths: impl IntoIterator<Item = PathBuf>) {
+        self.times = Self::modified_times(paths);
+    }
+
+    /// Checks whether the given files changed since the last reset.
+    ///
+    /// Added or removed files count as changes.
+    pub fn changed(&self, paths: impl IntoIterator<Item = PathBuf>) -> bool {
+        Self::modified_times(paths) != self.times
+    }
+
+    /// Checks whether a single file changed since the last reset.
+    ///
+    /// Files not watched before count as changed.
+    pub fn file_changed(&self, path: &Path) -> bool {
+        let modified = fs::metadata(path).and_then(|meta| meta.modified()).ok();
+        !self
+            .times
+            .iter()
+            .any(|(watched, time)| watched == path && *time == modified)
+    }
+
+    /// Reads the modification times of the given files.
+    fn modified_times(
+        paths: impl IntoIterator<Item = PathBuf>,
+    ) -> Vec<(PathBuf, Option<SystemTime>)> {
+        paths
+            .into_iter()
+            .map(|path| {
+                let modified = fs::metadata(&path).and_then(|meta| meta.modified()).ok();
+                (path, modified)
+            })
+            .collect()
+    }
+}
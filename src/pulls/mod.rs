@@ -0,0 +1,228 @@
+pub mod ui;
+
+use crate::{
+    reminder::custom::CustomReminder,
+    tracking::{
+        buff::{BuffState, Buffs},
+        uptime::UptimeStats,
+    },
+};
+use arc_util::{settings::HasSettings, tracking::CachedTracker};
+use arcdps::Profession;
+use std::collections::{BTreeMap, VecDeque};
+
+/// Maximum amount of pulls kept in the history.
+pub const PULLS_CAPACITY: usize = 50;
+
+/// History of squad snapshots taken at encounter starts.
+#[derive(Debug)]
+pub struct PullHistory {
+    /// Recorded pulls, oldest first.
+    pulls: VecDeque<Pull>,
+
+    /// Number of the last recorded pull.
+    count: usize,
+
+    /// Number of the selected pull.
+    selected: Option<usize>,
+}
+
+impl PullHistory {
+    /// Creates a new empty pull history.
+    pub const fn new() -> Self {
+        Self {
+            pulls: VecDeque::new(),
+            count: 0,
+            selected: None,
+        }
+    }
+
+    /// Records a snapshot of the tracked players for an encounter.
+    ///
+    /// `custom` contains the ids of custom buffs reminded for in the encounter.
+    pub fn record(
+        &mut self,
+        target_id: u64,
+        time: u64,
+        players: &CachedTracker<Buffs>,
+        custom: Vec<u32>,
+    ) -> &Pull {
+        if self.pulls.len() >= PULLS_CAPACITY {
+            self.pulls.pop_front();
+        }
+
+        self.count += 1;
+        let mut players = players
+            .iter()
+            .map(|entry| PullPlayer {
+                account: entry.player.account.clone(),
+                character: entry.player.character.clone(),
+                profession: entry.player.profession,
+                subgroup: entry.player.subgroup,
+                food: entry.data.food.state,
+                util: entry.data.util.state,
+                categories: entry
+                    .data
+                    .categories
+                    .iter()
+                    .map(|(name, buff)| (name.clone(), buff.state))
+                    .collect(),
+                custom: entry
+                    .data
+                    .custom
+                    .iter()
+                    .map(|(id, buff)| (*id, buff.state))
+                    .collect(),
+                uptime: None,
+            })
+            .collect::<Vec<_>>();
+        players.sort_by(|a, b| {
+            a.subgroup
+                .cmp(&b.subgroup)
+                .then_with(|| a.character.cmp(&b.character))
+        });
+
+        self.pulls.push_back(Pull {
+            number: self.count,
+            target_id,
+            time,
+            custom,
+            players,
+        });
+        self.pulls.back().unwrap()
+    }
+
+    /// Saves the uptime of the tracked players in the ended encounter to its pull.
+    ///
+    /// Only players whose encounter started at the time of the latest pull are updated.
+    pub fn finish(&mut self, players: &CachedTracker<Buffs>) {
+        let Some(pull) = self.pulls.back_mut() else {
+            return;
+        };
+        for player in &mut pull.players {
+            let entry = players
+                .iter()
+                .find(|entry| entry.player.account == player.account);
+            if let Some(entry) = entry {
+                if entry.data.uptime.started() == Some(pull.time) {
+                    player.uptime = Some(entry.data.uptime.encounter.clone());
+                }
+            }
+        }
+    }
+
+    /// Returns the selected pull.
+    fn selected_pull(&self) -> Option<&Pull> {
+        self.selected
+            .and_then(|number| self.pulls.iter().find(|pull| pull.number == number))
+    }
+
+    /// Removes all recorded pulls.
+    pub fn clear(&mut self) {
+        self.pulls.clear();
+        self.selected = None;
+    }
+}
+
+impl Default for PullHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HasSettings for PullHistory {
+    type Settings = ();
+
+    const SETTINGS_ID: &'static str = "pulls";
+
+    fn current_settings(&self) -> Self::Settings {}
+
+    fn load_settings(&mut self, _: Self::Settings) {}
+}
+
+/// Snapshot of the squad at an encounter start.
+#[derive(Debug, Clone)]
+pub struct Pull {
+    /// Number of the pull in the current session.
+    pub number: usize,
+
+    /// Id of the encounter target.
+    pub target_id: u64,
+
+    /// Time the encounter started.
+    pub time: u64,
+
+    /// Ids of custom buffs reminded for in the encounter.
+    pub custom: Vec<u32>,
+
+    /// Tracked players at the start.
+    pub players: Vec<PullPlayer>,
+}
+
+impl Pull {
+    /// Returns the custom reminders applying to the encounter.
+    pub fn reminders<'a>(
+        &'a self,
+        custom: &'a [CustomReminder],
+    ) -> impl Iterator<Item = &'a CustomReminder> + Clone {
+        custom
+            .iter()
+            .filter(|remind| self.custom.contains(&remind.id))
+    }
+
+    /// Returns the amount of players missing any consumable or custom buff.
+    pub fn missing(&self, custom: &[CustomReminder]) -> usize {
+        self.players
+            .iter()
+            .filter(|player| player.is_missing(self.reminders(custom)))
+            .count()
+    }
+}
+
+/// Buff states of a player at an encounter start.
+#[derive(Debug, Clone)]
+pub struct PullPlayer {
+    pub account: String,
+    pub character: String,
+    pub profession: Profession,
+    pub subgroup: usize,
+
+    /// Food buff state.
+    pub food: BuffState<u32>,
+
+    /// Utility buff state.
+    pub util: BuffState<u32>,
+
+    /// Consumable category buff states by category name.
+    pub categories: BTreeMap<String, BuffState<u32>>,
+
+    /// Custom buff states with their stack counts.
+    pub custom: BTreeMap<u32, BuffState<u32>>,
+
+    /// Uptime during the encounter, set once it ended.
+    pub uptime: Option<UptimeStats>,
+}
+
+impl PullPlayer {
+    /// Checks whether the player was missing any consumable or custom buff of the given reminders.
+    pub fn is_missing<'a>(&self, mut custom: impl Iterator<Item = &'a CustomReminder>) -> bool {
+        self.food == BuffState::None
+            || self.util == BuffState::None
+            || self
+                .categories
+                .values()
+                .any(|state| *state == BuffState::None)
+            || custom.any(|remind| !self.has_custom(remind))
+    }
+
+    /// Checks whether the player had the custom buff with enough stacks.
+    ///
+    /// Unknown states are not counted as missing.
+    pub fn has_custom(&self, remind: &CustomReminder) -> bool {
+        match self.custom.get(&remind.id).copied().unwrap_or_default() {
+            BuffState::Unknown => true,
+            BuffState::None => false,
+            BuffState::Some(stacks) => remind.has_stacks(stacks),
+        }
+    }
+}
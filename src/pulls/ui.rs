@@ -0,0 +1,260 @@
+use super::{PullHistory, PullPlayer};
+use crate::{
+    buff_ui,
+    data::{
+        Definitions, SkillNames, DIMINISHED, MALNOURISHED, NO_BUFF_TEXT, UNKNOWN_BUFF_TEXT,
+        UNKNOWN_STATE_TEXT,
+    },
+    reminder::custom::CustomReminder,
+    tracking::{
+        buff::{current_time, BuffState},
+        uptime::format_percent,
+    },
+};
+use arc_util::{
+    colors::{GREEN, RED, YELLOW},
+    ui::{render, Component, Windowable},
+};
+use arcdps::{
+    exports::{self, CoreColor},
+    imgui::{Selectable, SelectableFlags, TableColumnSetup, TableFlags, Ui},
+};
+
+pub type Props<'p> = (&'p Definitions, &'p [CustomReminder], &'p SkillNames);
+
+impl PullHistory {
+    /// Renders the cell for a consumable buff state.
+    fn render_buff_cell(
+        ui: &Ui,
+        defs: &Definitions,
+        colors: &exports::Colors,
+        state: BuffState<u32>,
+    ) {
+        let red = colors.core(CoreColor::LightRed).unwrap_or(RED);
+        let green = colors.core(CoreColor::LightGreen).unwrap_or(GREEN);
+        let yellow = colors.core(CoreColor::LightYellow).unwrap_or(YELLOW);
+
+        match state {
+            BuffState::Unknown => ui.text(UNKNOWN_STATE_TEXT),
+            BuffState::None => ui.text_colored(red, NO_BUFF_TEXT),
+            BuffState::Some(id) => match defs.definition(id).and_then(|def| def.data()) {
+                Some(data) => {
+                    let color = match data.id {
+                        MALNOURISHED | DIMINISHED => red,
+                        _ => green,
+                    };
+                    ui.text_colored(color, &data.display);
+                    buff_ui::render_buff_tooltip(ui, data, defs.display_language());
+                }
+                None => ui.text_colored(yellow, UNKNOWN_BUFF_TEXT),
+            },
+        }
+    }
+
+    /// Renders the table row for a player of a pull.
+    fn render_player(
+        ui: &Ui,
+        (defs, _, names): Props,
+        colors: &exports::Colors,
+        player: &PullPlayer,
+        custom: &[&CustomReminder],
+    ) {
+        let red = colors.core(CoreColor::LightRed).unwrap_or(RED);
+        let green = colors.core(CoreColor::LightGreen).unwrap_or(GREEN);
+        let yellow = colors.core(CoreColor::LightYellow).unwrap_or(YELLOW);
+
+        ui.table_next_row();
+
+        ui.table_next_column();
+        ui.text(format!("{:>2}", player.subgroup));
+
+        ui.table_next_column();
+        match colors.prof_base(player.profession) {
+            Some(color) => ui.text_colored(render::with_alpha(color, 1.0), &player.character),
+            None => ui.text(&player.character),
+        }
+        if ui.is_item_hovered() {
+            ui.tooltip_text(&player.account);
+        }
+
+        ui.table_next_column();
+        Self::render_buff_cell(ui, defs, colors, player.food);
+
+        ui.table_next_column();
+        Self::render_buff_cell(ui, defs, colors, player.util);
+
+        for category in defs.categories() {
+            ui.table_next_column();
+            let state = player
+                .categories
+                .get(category.name.as_ref())
+                .copied()
+                .unwrap_or_default();
+            Self::render_buff_cell(ui, defs, colors, state);
+        }
+
+        ui.table_next_column();
+        ui.group(|| {
+            for remind in custom {
+                let short = remind.short(names);
+                match player.custom.get(&remind.id).copied().unwrap_or_default() {
+                    BuffState::Unknown => ui.text(short),
+                    BuffState::None => ui.text_colored(red, short),
+                    BuffState::Some(stacks) if remind.has_stacks(stacks) => {
+                        ui.text_colored(green, short)
+                    }
+                    BuffState::Some(_) => ui.text_colored(yellow, short),
+                }
+                ui.same_line_with_spacing(0.0, 0.0);
+            }
+        });
+        if ui.is_item_hovered() {
+            ui.tooltip(|| {
+                for remind in custom {
+                    let name = remind.resolved_name(names);
+                    match player.custom.get(&remind.id).copied().unwrap_or_default() {
+                        BuffState::Unknown => ui.text(name),
+                        BuffState::None => ui.text_colored(red, name),
+                        BuffState::Some(stacks) if remind.has_stacks(stacks) => {
+                            ui.text_colored(green, format!("{name} x{stacks}"))
+                        }
+                        BuffState::Some(stacks) => ui.text_colored(
+                            yellow,
+                            format!("{name} x{stacks} (min {})", remind.min_stacks),
+                        ),
+                    }
+                }
+            });
+        }
+
+        ui.table_next_column();
+        match &player.uptime {
+            Some(uptime) => {
+                ui.text(format!(
+                    "{}/{}",
+                    format_percent(uptime.food_percent()),
+                    format_percent(uptime.util_percent())
+                ));
+                if ui.is_item_hovered() {
+                    ui.tooltip(|| {
+                        buff_ui::render_uptime_tooltip(
+                            ui,
+                            "Encounter",
+                            uptime,
+                            defs,
+                            custom.iter().copied(),
+                            names,
+                        )
+                    });
+                }
+            }
+            None => ui.text("-"),
+        }
+    }
+}
+
+impl Component<Props<'_>> for PullHistory {
+    fn render(&mut self, ui: &Ui, props: Props) {
+        let (defs, custom, _) = props;
+        let colors = exports::colors();
+        let red = colors.core(CoreColor::LightRed).unwrap_or(RED);
+        let green = colors.core(CoreColor::LightGreen).unwrap_or(GREEN);
+        let now = current_time();
+
+        if self.pulls.is_empty() {
+            ui.text("No pulls recorded");
+            return;
+        }
+
+        // recorded pulls, latest first
+        let mut clicked = None;
+        if let Some(_table) = ui.begin_table_header_with_sizing(
+            "##pulls-table",
+            [
+                TableColumnSetup::new("Pull"),
+                TableColumnSetup::new("Target"),
+                TableColumnSetup::new("Started"),
+                TableColumnSetup::new("Players"),
+                TableColumnSetup::new("Missing"),
+            ],
+            TableFlags::SIZING_STRETCH_PROP | TableFlags::PAD_OUTER_X | TableFlags::SCROLL_Y,
+            [0.0, 150.0],
+            0.0,
+        ) {
+            for pull in self.pulls.iter().rev() {
+                ui.table_next_row();
+
+                ui.table_next_column();
+                if Selectable::new(format!("#{}##pull-{}", pull.number, pull.number))
+                    .selected(self.selected == Some(pull.number))
+                    .flags(SelectableFlags::SPAN_ALL_COLUMNS)
+                    .build(ui)
+                {
+                    clicked = Some(pull.number);
+                }
+
+                ui.table_next_column();
+                ui.text(pull.target_id.to_string());
+
+                ui.table_next_column();
+                ui.text(format!(
+                    "{} ago",
+                    buff_ui::format_remaining(now.saturating_sub(pull.time))
+                ));
+
+                ui.table_next_column();
+                ui.text(pull.players.len().to_string());
+
+                ui.table_next_column();
+                match pull.missing(custom) {
+                    0 => ui.text_colored(green, "0"),
+                    missing => ui.text_colored(red, missing.to_string()),
+                }
+            }
+        }
+        if clicked.is_some() {
+            self.selected = clicked;
+        }
+
+        if ui.button("Clear") {
+            self.clear();
+        }
+
+        // players of selected pull
+        if let Some(pull) = self.selected_pull() {
+            ui.spacing();
+            ui.text(format!(
+                "Pull #{} on target {}",
+                pull.number, pull.target_id
+            ));
+
+            let reminders = pull.reminders(custom).collect::<Vec<_>>();
+            let columns = ["Sub", "Player", "Food", "Util"]
+                .into_iter()
+                .chain(defs.categories().map(|category| category.display_name()))
+                .chain(["Buffs", "Uptime"])
+                .collect::<Vec<_>>();
+
+            if let Some(_table) = ui.begin_table_with_sizing(
+                "##pull-players-table",
+                columns.len(),
+                TableFlags::SIZING_STRETCH_PROP | TableFlags::PAD_OUTER_X | TableFlags::SCROLL_Y,
+                [0.0, 300.0],
+                0.0,
+            ) {
+                for column in columns {
+                    ui.table_setup_column(column);
+                }
+                ui.table_headers_row();
+
+                for player in &pull.players {
+                    Self::render_player(ui, props, &colors, player, &reminders);
+                }
+            }
+        }
+    }
+}
+
+impl Windowable<Props<'_>> for PullHistory {
+    const CONTEXT_MENU: bool = false;
+}
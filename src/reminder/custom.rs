@@ -1,9 +1,8 @@
-use crate::data::{FRACTAL_MAPS, RAID_MAPS};
+use crate::data::{SkillNames, FRACTAL_MAPS, RAID_MAPS};
 use serde::{Deserialize, Serialize};
 use strum::{AsRefStr, EnumIter};
 
 /// Custom buff to remind for.
-// TODO: stacks?
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CustomReminder {
     /// Whether the reminder is active.
@@ -19,6 +18,12 @@ pub struct CustomReminder {
     /// [`GameMode`] this reminder is restricted to.
     #[serde(default)]
     pub mode: GameMode,
+
+    /// Minimum stacks of the buff required.
+    ///
+    /// `0` & `1` are satisfied by any stack.
+    #[serde(default)]
+    pub min_stacks: u32,
 }
 
 impl CustomReminder {
@@ -29,6 +34,7 @@ impl CustomReminder {
             id,
             name: name.into(),
             mode,
+            min_stacks: 0,
         }
     }
 
@@ -48,18 +54,27 @@ impl CustomReminder {
         ]
     }
 
-    /// Returns the name of the reminder while replacing empty names.
-    pub fn display_name(&self) -> &str {
+    /// Returns the name of the reminder while replacing empty names with the ingame skill name.
+    pub fn resolved_name<'a>(&'a self, names: &'a SkillNames) -> &'a str {
         if !self.name.is_empty() {
             &self.name
         } else {
-            "Unnamed"
+            names.get(self.id).unwrap_or("Unnamed")
         }
     }
 
+    /// Checks whether the given stacks satisfy the reminder.
+    pub fn has_stacks(&self, stacks: u32) -> bool {
+        stacks >= self.min_stacks.max(1)
+    }
+
     /// Returns the shortened reminder name.
-    pub fn short(&self) -> &str {
-        &self.display_name()[..1]
+    pub fn short<'a>(&'a self, names: &'a SkillNames) -> &'a str {
+        let name = self.resolved_name(names);
+        match name.char_indices().nth(1) {
+            Some((end, _)) => &name[..end],
+            None => name,
+        }
     }
 }
 
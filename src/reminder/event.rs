@@ -1,6 +1,6 @@
 use super::{Encounter, Reminder};
 use crate::{
-    data::{DIMINISHED, MALNOURISHED},
+    data::{Slot, DIMINISHED, MALNOURISHED},
     tracking::buff::{BuffState, Buffs},
 };
 use arc_util::tracking::CachedTracker;
@@ -21,6 +21,7 @@ impl Reminder {
             target_id,
             start_time,
             pending_check: self.settings.encounter_start,
+            pending_snapshot: true,
         });
     }
 
@@ -28,28 +29,54 @@ impl Reminder {
     pub fn change_encounter(&mut self, target_id: u64, time: u64) {
         // only change id if pending, otherwise start as new encounter
         match &mut self.encounter {
-            Some(encounter) if encounter.pending_check => encounter.target_id = target_id,
+            Some(encounter) if encounter.pending_check || encounter.pending_snapshot => {
+                encounter.target_id = target_id
+            }
             _ => self.start_encounter(target_id, time),
         }
     }
 
     /// Handles encounter end.
-    pub fn end_encounter(&mut self, players: &CachedTracker<Buffs>) {
+    ///
+    /// `categories` contains the names of the current consumable categories.
+    pub fn end_encounter<'a>(
+        &mut self,
+        players: &CachedTracker<Buffs>,
+        categories: impl IntoIterator<Item = &'a str>,
+    ) {
         if self.settings.encounter_end {
-            self.check_self_all(players);
+            self.check_self_all(players, categories);
         }
         self.encounter = None;
     }
 
     /// Updates pending buff check.
-    pub fn update_pending_check(&mut self, players: &CachedTracker<Buffs>, time: u64) {
-        // handle pending check
+    ///
+    /// `categories` contains the names of the current consumable categories.
+    /// Returns the target id & start time of the encounter once its squad snapshot is due.
+    pub fn update_pending_check<'a>(
+        &mut self,
+        players: &CachedTracker<Buffs>,
+        categories: impl IntoIterator<Item = &'a str>,
+        time: u64,
+    ) -> Option<(u64, u64)> {
+        let mut snapshot = None;
         if let Some(encounter) = &mut self.encounter {
-            if encounter.pending_check && time >= encounter.start_time + CHECK_TIME_DIFF {
-                encounter.pending_check = false;
-                self.check_self_all(players);
+            if time >= encounter.start_time + CHECK_TIME_DIFF {
+                // handle pending snapshot
+                if encounter.pending_snapshot {
+                    encounter.pending_snapshot = false;
+                    snapshot = Some((encounter.target_id, encounter.start_time));
+                }
+
+                // handle pending check
+                if encounter.pending_check {
+                    encounter.pending_check = false;
+                    self.check_self_all(players, categories);
+                }
             }
         }
+        snapshot
     }
 
     /// Handles a buff apply to self.
@@ -63,17 +90,14 @@ impl Reminder {
         }
     }
 
-    /// Handles a food remove from self.
-    pub fn self_food_remove(&mut self, buffs: &Buffs) {
-        if self.settings.during_encounter {
-            self.check_food(buffs);
-        }
-    }
-
-    /// Handles an utility remove from self.
-    pub fn self_util_remove(&mut self, buffs: &Buffs) {
+    /// Handles a consumable remove from self.
+    pub fn self_slot_remove(&mut self, slot: Slot, buffs: &Buffs) {
         if self.settings.during_encounter {
-            self.check_util(buffs);
+            match slot {
+                Slot::Food => self.check_food(buffs),
+                Slot::Util => self.check_util(buffs),
+                Slot::Category(name) => self.check_category(name, buffs),
+            }
         }
     }
 
@@ -94,10 +118,17 @@ impl Reminder {
     }
 
     /// Performs a check for all reminders.
-    fn check_self_all(&mut self, players: &CachedTracker<Buffs>) {
+    fn check_self_all<'a>(
+        &mut self,
+        players: &CachedTracker<Buffs>,
+        categories: impl IntoIterator<Item = &'a str>,
+    ) {
         if let Some(player) = players.get_self() {
             self.check_food(&player.data);
             self.check_util(&player.data);
+            for name in categories {
+                self.check_category(name, &player.data);
+            }
             self.check_custom(&player.data);
         }
     }
@@ -124,12 +155,30 @@ impl Reminder {
         }
     }
 
-    /// Checks for missing custom tracked buffs.
+    /// Checks for missing consumable category buff.
+    fn check_category(&mut self, name: &str, buffs: &Buffs) {
+        if self.can_remind() {
+            let state = buffs.slot_state(Slot::Category(name));
+            debug!("Checking {} on self: {:?}", name, state);
+            if let BuffState::None = state {
+                self.trigger_category(name);
+            }
+        }
+    }
+
+    /// Checks for missing custom tracked buffs or missing stacks.
     fn check_custom(&mut self, buffs: &Buffs) {
         if self.can_remind() {
             for (id, buff) in &buffs.custom {
                 debug!("Checking custom buff {} on self: {:?}", id, buff.state);
-                if let BuffState::None = buff.state {
+                let missing = match buff.state {
+                    BuffState::None => true,
+                    BuffState::Some(stacks) => self
+                        .custom(*id)
+                        .is_some_and(|remind| !remind.has_stacks(stacks)),
+                    BuffState::Unknown => false,
+                };
+                if missing {
                     self.trigger_custom(*id);
                 }
             }
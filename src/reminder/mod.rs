@@ -27,6 +27,9 @@ pub struct Reminder {
     /// Timestamp of utility reminder trigger.
     util_trigger: Option<Instant>,
 
+    /// Timestamps of consumable category reminder triggers by category name.
+    category_triggers: BTreeMap<String, Instant>,
+
     /// Timestamps of custom buff reminder triggers.
     custom_triggers: BTreeMap<u32, Instant>,
 
@@ -54,6 +57,7 @@ impl Reminder {
             settings: ReminderSettings::new(),
             food_trigger: None,
             util_trigger: None,
+            category_triggers: BTreeMap::new(),
             custom_triggers: BTreeMap::new(),
             encounter: None,
             custom_reset: false,
@@ -73,10 +77,13 @@ impl Reminder {
         &self.settings.custom
     }
 
-    /// Triggers all reminders.
-    pub fn trigger_all(&mut self) {
+    /// Triggers all reminders, including the given consumable categories.
+    pub fn trigger_all<'a>(&mut self, categories: impl IntoIterator<Item = &'a str>) {
         self.trigger_food();
         self.trigger_util();
+        for name in categories {
+            self.trigger_category(name);
+        }
         let ids = self
             .all_custom()
             .iter()
@@ -103,18 +110,38 @@ impl Reminder {
         }
     }
 
+    /// Triggers the reminder for the consumable category with the given name.
+    pub fn trigger_category(&mut self, name: &str) {
+        if self.settings.category(name) {
+            info!("{name} reminder triggered");
+            self.category_triggers.insert(name.into(), Instant::now());
+        }
+    }
+
+    /// Checks whether the custom reminder applies to the current map.
+    fn custom_applies(&self, remind: &CustomReminder) -> bool {
+        if let Some(mumble) = &self.mumble {
+            let link = mumble.read();
+            remind.mode.is_map(link.context.map_id)
+        } else {
+            // no mumble, only apply all game modes
+            remind.mode == GameMode::All
+        }
+    }
+
+    /// Returns the ids of active custom reminders applying to the current map.
+    pub fn applicable_custom(&self) -> Vec<u32> {
+        self.all_custom()
+            .iter()
+            .filter(|remind| remind.active && self.custom_applies(remind))
+            .map(|remind| remind.id)
+            .collect()
+    }
+
     /// Triggers the custom buff reminder.
     pub fn trigger_custom(&mut self, id: u32) {
         if let Some(remind @ CustomReminder { active: true, .. }) = self.custom(id) {
-            let applies = if let Some(mumble) = &self.mumble {
-                let link = mumble.read();
-                remind.mode.is_map(link.context.map_id)
-            } else {
-                // no mumble, only apply all game modes
-                remind.mode == GameMode::All
-            };
-
-            if applies {
+            if self.custom_applies(remind) {
                 info!("Custom reminder triggered");
                 self.custom_triggers.insert(remind.id, Instant::now());
             }
@@ -138,4 +165,7 @@ struct Encounter {
 
     /// Whether there is a pending check for the encounter.
     pub pending_check: bool,
+
+    /// Whether there is a pending squad snapshot for the encounter.
+    pub pending_snapshot: bool,
 }
@@ -1,7 +1,7 @@
 use super::{custom::CustomReminder, Reminder};
 use arc_util::settings::HasSettings;
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::{collections::BTreeMap, time::Duration};
 
 // TODO: game mode setting for inbuilt food & util reminders
 
@@ -14,6 +14,11 @@ pub struct ReminderSettings {
     /// Whether to remind for utility.
     pub util: bool,
 
+    /// Whether to remind for consumable categories by category name.
+    ///
+    /// Categories without entry are reminded for.
+    pub categories: BTreeMap<String, bool>,
+
     /// User-defined custom reminders for buffs.
     pub custom: Vec<CustomReminder>,
 
@@ -45,6 +50,7 @@ impl ReminderSettings {
         Self {
             food: true,
             util: true,
+            categories: BTreeMap::new(),
             custom: CustomReminder::defaults(),
             duration: Reminder::DEFAULT_DURATION,
             position: 0.2,
@@ -57,6 +63,18 @@ impl ReminderSettings {
     }
 }
 
+impl ReminderSettings {
+    /// Returns whether to remind for the consumable category with the given name.
+    pub fn category(&self, name: &str) -> bool {
+        self.categories.get(name).copied().unwrap_or(true)
+    }
+
+    /// Returns a mutable reference to whether to remind for the consumable category with the given name.
+    pub fn category_mut(&mut self, name: &str) -> &mut bool {
+        self.categories.entry(name.into()).or_insert(true)
+    }
+}
+
 impl Default for ReminderSettings {
     fn default() -> Self {
         Self::new()
@@ -1,4 +1,5 @@
 use super::{custom::CustomReminder, Reminder};
+use crate::data::SkillNames;
 use arc_util::{
     colors::RED,
     ui::{render, Component},
@@ -15,6 +16,8 @@ use std::time::{Duration, Instant};
 /// Font size used by the reminder.
 const FONT_SIZE: f32 = 2.0;
 
+pub type Props<'p> = &'p SkillNames;
+
 impl Reminder {
     /// Checks if a trigger is currently active and resets it if necessary.
     fn update_trigger(trigger: &mut Option<Instant>, duration: Duration) -> bool {
@@ -57,16 +60,18 @@ impl Reminder {
     }
 }
 
-impl Component<()> for Reminder {
-    fn render(&mut self, ui: &Ui, _: ()) {
+impl Component<Props<'_>> for Reminder {
+    fn render(&mut self, ui: &Ui, names: Props) {
         // update triggers
         let food = Self::update_trigger(&mut self.food_trigger, self.settings.duration);
         let util = Self::update_trigger(&mut self.util_trigger, self.settings.duration);
+        self.category_triggers
+            .retain(|_, time| Self::is_triggered(*time, self.settings.duration));
         self.custom_triggers
             .retain(|_, time| Self::is_triggered(*time, self.settings.duration));
 
         // check if any is triggered
-        if food || util || !self.custom_triggers.is_empty() {
+        if food || util || !self.category_triggers.is_empty() || !self.custom_triggers.is_empty() {
             // calculate window position
             let [screen_width, screen_height] = ui.io().display_size;
 
@@ -95,10 +100,18 @@ impl Component<()> for Reminder {
                         (false, false) => {}
                     }
 
+                    // consumable categories
+                    for name in self.category_triggers.keys() {
+                        Self::render_text(ui, &format!("{name} reminder!"));
+                    }
+
                     // custom reminders
                     for id in self.custom_triggers.keys() {
                         if let Some(remind) = self.custom(*id) {
-                            Self::render_text(ui, &format!("{} reminder!", remind.display_name()));
+                            Self::render_text(
+                                ui,
+                                &format!("{} reminder!", remind.resolved_name(names)),
+                            );
                         }
                     }
                 });
@@ -1,7 +1,17 @@
+use super::{
+    history::{BuffChange, BuffHistory, HistoryBuff},
+    uptime::{Present, Uptime, UptimeStats},
+};
+use crate::data::Slot;
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
+use windows::Win32::Media::timeGetTime;
 
-// TODO: track buff duration & reset to unset when duration runs out?
+/// Returns the current time in the same clock as combat event times.
+pub fn current_time() -> u64 {
+    // arc uses timegettime for event times
+    unsafe { timeGetTime() }.into()
+}
 
 /// Buff information.
 #[derive(Debug, Clone)]
@@ -12,25 +22,47 @@ pub struct Buffs {
     /// Current utility buff applied to the player.
     pub util: TrackedBuff<u32>,
 
-    /// Custom tracked buffs.
-    pub custom: BTreeMap<u32, TrackedBuff<()>>,
+    /// Current buffs of user-defined consumable categories by category name.
+    pub categories: BTreeMap<String, TrackedBuff<u32>>,
+
+    /// Custom tracked buffs with their stack counts.
+    pub custom: BTreeMap<u32, TrackedBuff<u32>>,
+
+    /// Ids of present custom buffs with stacks to be recounted from initial buff events.
+    recount: BTreeSet<u32>,
+
+    /// History of buff state changes.
+    pub history: BuffHistory,
+
+    /// Uptime of buffs during encounters.
+    pub uptime: Uptime,
 }
 
 impl Buffs {
     /// Creates new buff information with initial states.
     pub fn new() -> Self {
-        Self::with_states(BuffState::Unknown, BuffState::Unknown, BTreeMap::new())
+        Self::with_states(
+            BuffState::Unknown,
+            BuffState::Unknown,
+            BTreeMap::new(),
+            BTreeMap::new(),
+        )
     }
 
     /// Creates new buff information with given buff states.
     pub fn with_states(
         food: BuffState<u32>,
         util: BuffState<u32>,
-        custom: BTreeMap<u32, BuffState<()>>,
+        categories: BTreeMap<String, BuffState<u32>>,
+        custom: BTreeMap<u32, BuffState<u32>>,
     ) -> Self {
         Self::with_buffs(
             TrackedBuff::new(food),
             TrackedBuff::new(util),
+            categories
+                .into_iter()
+                .map(|(name, state)| (name, TrackedBuff::new(state)))
+                .collect(),
             custom
                 .into_iter()
                 .map(|(id, state)| (id, TrackedBuff::new(state)))
@@ -42,96 +74,186 @@ impl Buffs {
     pub const fn with_buffs(
         food: TrackedBuff<u32>,
         util: TrackedBuff<u32>,
-        custom: BTreeMap<u32, TrackedBuff<()>>,
+        categories: BTreeMap<String, TrackedBuff<u32>>,
+        custom: BTreeMap<u32, TrackedBuff<u32>>,
     ) -> Self {
-        Self { food, util, custom }
+        Self {
+            food,
+            util,
+            categories,
+            custom,
+            recount: BTreeSet::new(),
+            history: BuffHistory::new(),
+            uptime: Uptime::new(),
+        }
     }
 
     /// Resets all buffs.
     pub fn reset_buffs(&mut self) {
         self.food = Default::default();
         self.util = Default::default();
+        self.categories = Default::default();
         self.custom = Default::default();
+        self.recount = Default::default();
     }
 
     /// Sets all unset buff states to none.
-    pub fn unset_to_none(&mut self, time: u64, custom_ids: impl Iterator<Item = u32>) {
+    ///
+    /// Stacks of present custom buffs are recounted from the following initial buff events.
+    pub fn unset_to_none<'a>(
+        &mut self,
+        time: u64,
+        categories: impl Iterator<Item = &'a str>,
+        custom_ids: impl Iterator<Item = u32>,
+    ) {
         self.food.update_if_unknown(BuffState::None, time);
         self.util.update_if_unknown(BuffState::None, time);
-        for id in custom_ids {
-            self.custom
-                .entry(id)
-                .or_default()
+        for category in categories {
+            self.slot_mut(Slot::Category(category))
                 .update_if_unknown(BuffState::None, time);
         }
+        for id in custom_ids {
+            let buff = self.custom.entry(id).or_default();
+            buff.update_if_unknown(BuffState::None, time);
+            if let BuffState::Some(_) = buff.state {
+                self.recount.insert(id);
+            }
+        }
     }
 
-    /// Applies a food buff to the player.
-    ///
-    /// Returns `true` if this update changed the buff state.
-    pub fn apply_food(&mut self, food: u32, time: u64) -> bool {
-        self.food.update(BuffState::Some(food), time, true)
+    /// Returns the buffs currently present for uptime.
+    fn present(&self) -> Present {
+        let present = |state| match state {
+            BuffState::Unknown => None,
+            BuffState::None => Some(false),
+            BuffState::Some(_) => Some(true),
+        };
+        Present {
+            food: present(self.food.state),
+            util: present(self.util.state),
+            categories: self
+                .categories
+                .iter()
+                .filter_map(|(name, buff)| {
+                    present(buff.state).map(|present| (name.clone(), present))
+                })
+                .collect(),
+            custom: self
+                .custom
+                .iter()
+                .filter_map(|(id, buff)| present(buff.state).map(|present| (*id, present)))
+                .collect(),
+        }
     }
 
-    /// Removes the current food buff from the player.
+    /// Accounts the uptime of the current buffs until the given time.
     ///
-    /// Has no effect if the current buff is different from the passed buff.
-    /// Passing [`None`] indicates a [`BuffState::Unknown`].
-    /// [`BuffState::Unset`] is always removed.
+    /// Needs to happen before any buff state changes.
+    fn account(&mut self, time: u64) {
+        if self.uptime.in_encounter() {
+            let present = self.present();
+            self.uptime.update(time, &present);
+        }
+    }
+
+    /// Starts uptime tracking for a new encounter.
     ///
-    /// Returns `false` if this update was ignored.
-    pub fn remove_food(&mut self, food: u32, time: u64) -> bool {
-        let changed = match self.food.state {
-            BuffState::Some(applied) => food == applied,
-            _ => true,
-        };
-        if changed {
-            self.food.update(BuffState::None, time, false)
-        } else {
-            false
+    /// A previous encounter without end is dropped without accounting the time since.
+    pub fn start_encounter(&mut self, time: u64) {
+        self.uptime.start(time);
+    }
+
+    /// Ends uptime tracking for the current encounter.
+    pub fn end_encounter(&mut self, time: u64) {
+        let present = self.present();
+        self.uptime.end(time, &present);
+    }
+
+    /// Returns the encounter & session uptime until the given time.
+    pub fn current_uptime(&self, now: u64) -> (UptimeStats, UptimeStats) {
+        self.uptime.current(now, &self.present())
+    }
+
+    /// Returns the tracked buff in the given consumable slot, if present.
+    pub fn slot(&self, slot: Slot) -> Option<&TrackedBuff<u32>> {
+        match slot {
+            Slot::Food => Some(&self.food),
+            Slot::Util => Some(&self.util),
+            Slot::Category(name) => self.categories.get(name),
+        }
+    }
+
+    /// Returns the [`BuffState`] of the given consumable slot.
+    pub fn slot_state(&self, slot: Slot) -> BuffState<u32> {
+        self.slot(slot).map(|buff| buff.state).unwrap_or_default()
+    }
+
+    /// Returns the tracked buff in the given consumable slot, inserting categories as needed.
+    fn slot_mut(&mut self, slot: Slot) -> &mut TrackedBuff<u32> {
+        match slot {
+            Slot::Food => &mut self.food,
+            Slot::Util => &mut self.util,
+            Slot::Category(name) => self.categories.entry(name.into()).or_default(),
         }
     }
 
-    /// Applies an utility buff to the player.
+    /// Applies a consumable buff in the given slot to the player.
+    ///
+    /// `initial` marks initial buff events, which keep the time of application for an already applied buff.
     ///
     /// Returns `false` if this update was ignored.
-    pub fn apply_util(&mut self, util: u32, time: u64) -> bool {
-        self.util.update(BuffState::Some(util), time, true)
+    pub fn apply(&mut self, slot: Slot, buff_id: u32, time: u64, initial: bool) -> bool {
+        self.account(time);
+        let buff = self.slot_mut(slot);
+        if initial && buff.state == BuffState::Some(buff_id) {
+            false
+        } else {
+            buff.update(BuffState::Some(buff_id), time, true)
+        }
     }
 
-    /// Removes the current utility buff from the player.
+    /// Removes the current consumable buff in the given slot from the player.
     ///
     /// Has no effect if the current buff is different from the passed buff.
-    /// Passing [`None`] indicates a [`BuffState::Unknown`].
-    /// [`BuffState::Unset`] is always removed.
+    /// [`BuffState::Unknown`] & [`BuffState::None`] are always replaced.
     ///
     /// Returns `false` if this update was ignored.
-    pub fn remove_util(&mut self, util: u32, time: u64) -> bool {
-        let changed = match self.util.state {
-            BuffState::Some(applied) => util == applied,
+    pub fn remove(&mut self, slot: Slot, buff_id: u32, time: u64) -> bool {
+        self.account(time);
+        let buff = self.slot_mut(slot);
+        let changed = match buff.state {
+            BuffState::Some(applied) => buff_id == applied,
             _ => true,
         };
         if changed {
-            self.util.update(BuffState::None, time, false)
+            buff.update(BuffState::None, time, false)
         } else {
             false
         }
     }
 
-    /// Applies a custom tracked buff to the player.
+    /// Applies a stack of a custom tracked buff to the player.
+    ///
+    /// `initial` marks initial buff events, which restart the count of stacks to be recounted.
     ///
     /// Returns `false` if this update was ignored.
-    pub fn apply_custom(&mut self, id: u32, time: u64) -> bool {
-        self.custom
-            .entry(id)
-            .or_default()
-            .update(BuffState::Some(()), time, true)
+    pub fn apply_custom(&mut self, id: u32, time: u64, initial: bool) -> bool {
+        self.account(time);
+        let recount = self.recount.remove(&id) && initial;
+        let buff = self.custom.entry(id).or_default();
+        let stacks = match buff.state {
+            BuffState::Some(stacks) if !recount => stacks + 1,
+            _ => 1,
+        };
+        buff.update(BuffState::Some(stacks), time, true)
     }
 
-    /// Removes a custom tracked buff from the player.
+    /// Removes all stacks of a custom tracked buff from the player.
     ///
     /// Returns `false` if this update was ignored.
     pub fn remove_custom(&mut self, id: u32, time: u64) -> bool {
+        self.account(time);
+        self.recount.remove(&id);
         if let Some(buff) = self.custom.get_mut(&id) {
             buff.update(BuffState::None, time, false)
         } else {
@@ -139,8 +261,95 @@ impl Buffs {
         }
     }
 
-    /// Returns the [`BuffState`] of the given custom buff id.
-    pub fn custom_state(&self, id: u32) -> BuffState<()> {
+    /// Removes a single stack of a custom tracked buff from the player.
+    ///
+    /// Stacks are replaced at the same time when reaching the stack limit, so same time updates are always counted.
+    /// Has no effect if the current stacks are not known.
+    ///
+    /// Returns `false` if this update was ignored.
+    pub fn remove_custom_stack(&mut self, id: u32, time: u64) -> bool {
+        self.account(time);
+        self.recount.remove(&id);
+        match self.custom.get_mut(&id) {
+            Some(buff) => match buff.state {
+                BuffState::Some(stacks) if stacks > 1 => {
+                    buff.update(BuffState::Some(stacks - 1), time, true)
+                }
+                BuffState::Some(_) => buff.update(BuffState::None, time, true),
+                _ => false,
+            },
+            None => false,
+        }
+    }
+
+    /// Records the current state of the given buff in the history.
+    ///
+    /// Custom buffs are only recorded when becoming present or absent,
+    /// stack changes would push out the consumable changes.
+    pub fn record(&mut self, buff: HistoryBuff, time: u64, encounter: bool) {
+        let state = match &buff {
+            HistoryBuff::Food => self.food.state,
+            HistoryBuff::Util => self.util.state,
+            HistoryBuff::Category(name) => self.slot_state(Slot::Category(name)),
+            HistoryBuff::Custom(id) => self.custom_state(*id),
+        };
+        if let HistoryBuff::Custom(_) = buff {
+            let present = |state| matches!(state, BuffState::Some(_));
+            if let Some(last) = self.history.last(&buff) {
+                if present(last.state) == present(state) {
+                    return;
+                }
+            }
+        }
+        self.history.push(BuffChange {
+            time,
+            buff,
+            state,
+            encounter,
+        });
+    }
+
+    /// Resets consumable buffs to [`BuffState::Unknown`] when their predicted expiry has passed.
+    ///
+    /// `duration` returns the duration in milliseconds for a buff id.
+    /// `expired` is called with each expired slot.
+    pub fn expire(
+        &mut self,
+        now: u64,
+        duration: impl Fn(u32) -> Option<u64>,
+        mut expired: impl FnMut(Slot),
+    ) {
+        // account uptime before any buff expires
+        let expires = |buff: &TrackedBuff<u32>| match buff.state {
+            BuffState::Some(id) => duration(id)
+                .and_then(|dur| buff.expiry(dur))
+                .is_some_and(|expiry| now >= expiry),
+            _ => false,
+        };
+        if expires(&self.food) || expires(&self.util) || self.categories.values().any(expires) {
+            self.account(now);
+        }
+
+        let expire = |buff: &mut TrackedBuff<u32>| match buff.state {
+            BuffState::Some(id) => duration(id).is_some_and(|dur| buff.expire(dur, now)),
+            _ => false,
+        };
+
+        if expire(&mut self.food) {
+            expired(Slot::Food);
+        }
+        if expire(&mut self.util) {
+            expired(Slot::Util);
+        }
+        for (name, buff) in &mut self.categories {
+            if expire(buff) {
+                expired(Slot::Category(name));
+            }
+        }
+    }
+
+    /// Returns the [`BuffState`] of the given custom buff id, containing the stack count.
+    pub fn custom_state(&self, id: u32) -> BuffState<u32> {
         self.custom
             .get(&id)
             .map(|buff| buff.state)
@@ -187,6 +396,36 @@ impl<T> TrackedBuff<T> {
         }
     }
 
+    /// Returns the predicted time of expiry for the given buff duration.
+    ///
+    /// Returns [`None`] if no buff is applied or the time of application is not known.
+    pub fn expiry(&self, duration: u64) -> Option<u64> {
+        match self.state {
+            BuffState::Some(_) if self.time > 0 => Some(self.time + duration),
+            _ => None,
+        }
+    }
+
+    /// Returns the predicted remaining time for the given buff duration.
+    pub fn remaining(&self, duration: u64, now: u64) -> Option<u64> {
+        self.expiry(duration)
+            .map(|expiry| expiry.saturating_sub(now))
+    }
+
+    /// Resets the tracked buff to [`BuffState::Unknown`] if the predicted expiry has passed.
+    ///
+    /// Returns `true` if the buff expired.
+    pub fn expire(&mut self, duration: u64, now: u64) -> bool {
+        match self.expiry(duration) {
+            Some(expiry) if now >= expiry => {
+                self.state = BuffState::Unknown;
+                self.time = expiry;
+                true
+            }
+            _ => false,
+        }
+    }
+
     /// Updates the tracked buff state if it is currently [`BuffState::Unknown`].
     ///
     /// Returns `false` if this update was ignored.
@@ -225,3 +464,105 @@ pub enum BuffState<T> {
     /// Some buff is applied.
     Some(T),
 }
+
+impl<T> BuffState<T> {
+    /// Maps the applied buff with the given function.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> BuffState<U> {
+        match self {
+            Self::Unknown => BuffState::Unknown,
+            Self::None => BuffState::None,
+            Self::Some(value) => BuffState::Some(f(value)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn initial_apply() {
+        const DURATION: u64 = 1000;
+
+        let mut buffs = Buffs::new();
+        assert!(buffs.apply(Slot::Food, 1, 100, false));
+
+        // initial events at a later pull keep the time of application
+        assert!(!buffs.apply(Slot::Food, 1, 500, true));
+        assert_eq!(buffs.food.expiry(DURATION), Some(1100));
+        buffs.expire(1100, |_| Some(DURATION), |_| ());
+        assert_eq!(buffs.slot_state(Slot::Food), BuffState::Unknown);
+
+        // initial events for unknown or different buffs are applied
+        assert!(buffs.apply(Slot::Food, 1, 1200, true));
+        assert!(buffs.apply(Slot::Food, 2, 1300, true));
+        assert_eq!(buffs.food.expiry(DURATION), Some(2300));
+    }
+
+    #[test]
+    fn custom_stacks() {
+        let mut buffs = Buffs::new();
+        assert!(buffs.apply_custom(1, 10, false));
+        assert!(buffs.apply_custom(1, 10, false));
+        assert!(buffs.apply_custom(1, 20, false));
+        assert_eq!(buffs.custom_state(1), BuffState::Some(3));
+
+        // same time stack removes are counted
+        assert!(buffs.remove_custom_stack(1, 30));
+        assert!(buffs.remove_custom_stack(1, 30));
+        assert_eq!(buffs.custom_state(1), BuffState::Some(1));
+        assert!(buffs.remove_custom_stack(1, 40));
+        assert_eq!(buffs.custom_state(1), BuffState::None);
+        assert!(!buffs.remove_custom_stack(1, 50));
+        assert!(!buffs.remove_custom_stack(2, 50));
+
+        // older removes are ignored
+        assert!(buffs.apply_custom(1, 60, false));
+        assert!(!buffs.remove_custom(1, 50));
+        assert!(buffs.remove_custom(1, 70));
+        assert_eq!(buffs.custom_state(1), BuffState::None);
+    }
+
+    #[test]
+    fn custom_recount() {
+        let mut buffs = Buffs::new();
+        buffs.apply_custom(1, 10, false);
+        buffs.apply_custom(1, 10, false);
+        buffs.unset_to_none(100, [].into_iter(), [1, 2].into_iter());
+        assert_eq!(buffs.custom_state(1), BuffState::Some(2));
+        assert_eq!(buffs.custom_state(2), BuffState::None);
+
+        // initial events recount present stacks
+        buffs.apply_custom(1, 100, true);
+        assert_eq!(buffs.custom_state(1), BuffState::Some(1));
+        buffs.apply_custom(1, 100, true);
+        buffs.apply_custom(1, 100, true);
+        assert_eq!(buffs.custom_state(1), BuffState::Some(3));
+
+        // other events keep the known stacks
+        buffs.unset_to_none(200, [].into_iter(), [1].into_iter());
+        buffs.apply_custom(1, 300, false);
+        assert_eq!(buffs.custom_state(1), BuffState::Some(4));
+    }
+
+    #[test]
+    fn custom_history() {
+        let mut buffs = Buffs::new();
+        for time in 1..=3 {
+            buffs.apply_custom(1, time, false);
+            buffs.record(HistoryBuff::Custom(1), time, false);
+        }
+        buffs.remove_custom_stack(1, 4);
+        buffs.record(HistoryBuff::Custom(1), 4, false);
+        buffs.remove_custom(1, 5);
+        buffs.record(HistoryBuff::Custom(1), 5, false);
+
+        // only presence changes are recorded
+        let states = buffs
+            .history
+            .iter()
+            .map(|change| (change.time, change.state))
+            .collect::<Vec<_>>();
+        assert_eq!(states, [(1, BuffState::Some(1)), (5, BuffState::None)]);
+    }
+}
@@ -0,0 +1,98 @@
+use super::buff::BuffState;
+use crate::data::Slot;
+use std::collections::VecDeque;
+
+/// Maximum amount of buff changes kept per player.
+pub const HISTORY_CAPACITY: usize = 64;
+
+/// Bounded history of buff state changes.
+///
+/// Oldest changes are dropped once the capacity is reached.
+#[derive(Debug, Clone)]
+pub struct BuffHistory {
+    /// Recorded changes, oldest first.
+    changes: VecDeque<BuffChange>,
+}
+
+impl BuffHistory {
+    /// Creates a new empty history.
+    pub const fn new() -> Self {
+        Self {
+            changes: VecDeque::new(),
+        }
+    }
+
+    /// Records a buff change.
+    pub fn push(&mut self, change: BuffChange) {
+        if self.changes.len() >= HISTORY_CAPACITY {
+            self.changes.pop_front();
+        }
+        self.changes.push_back(change);
+    }
+
+    /// Returns the latest recorded change of the given buff.
+    pub fn last(&self, buff: &HistoryBuff) -> Option<&BuffChange> {
+        self.changes
+            .iter()
+            .rev()
+            .find(|change| change.buff == *buff)
+    }
+
+    /// Returns an iterator over the recorded changes, oldest first.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &BuffChange> {
+        self.changes.iter()
+    }
+
+    /// Checks whether no changes are recorded.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Removes all recorded changes.
+    pub fn clear(&mut self) {
+        self.changes.clear();
+    }
+}
+
+impl Default for BuffHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Recorded buff state change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuffChange {
+    /// Timestamp of the event.
+    pub time: u64,
+
+    /// Changed buff.
+    pub buff: HistoryBuff,
+
+    /// New buff state.
+    ///
+    /// Contains the buff id for consumables and the stack count for custom buffs.
+    pub state: BuffState<u32>,
+
+    /// Whether the change happened during an encounter.
+    pub encounter: bool,
+}
+
+/// Buff tracked in the history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HistoryBuff {
+    Food,
+    Util,
+    Category(String),
+    Custom(u32),
+}
+
+impl From<Slot<'_>> for HistoryBuff {
+    fn from(slot: Slot) -> Self {
+        match slot {
+            Slot::Food => Self::Food,
+            Slot::Util => Self::Util,
+            Slot::Category(name) => Self::Category(name.into()),
+        }
+    }
+}
@@ -1,12 +1,17 @@
 pub mod buff;
+pub mod history;
 pub mod settings;
 pub mod ui;
+pub mod uptime;
 
 use self::buff::{BuffState, Buffs};
 use self::settings::TrackerSettings;
-use crate::builds::Builds;
+use crate::{
+    builds::Builds,
+    data::{Definitions, Slot},
+};
 use arc_util::tracking::{CachedTracker, Entry, Player};
-use log::{debug, log_enabled, Level};
+use log::{debug, info, log_enabled, Level};
 use std::cmp::Reverse;
 use windows::System::VirtualKey;
 
@@ -30,6 +35,9 @@ pub struct Tracker {
 
     /// User-defined builds.
     builds: Builds,
+
+    /// Character name of the player with the timeline open.
+    timeline: Option<String>,
 }
 
 #[allow(dead_code)]
@@ -46,6 +54,7 @@ impl Tracker {
             reverse: false,
             chars_reset: false,
             builds: Builds::new(),
+            timeline: None,
         }
     }
 
@@ -58,8 +67,8 @@ impl Tracker {
         if log_enabled!(Level::Debug) && cached {
             let Entry { player, data } = self.players.player(id).unwrap();
             debug!(
-                "Cached for {}: Food {:?}, Util {:?}, Custom {:?}",
-                player.character, data.food.state, data.util.state, data.custom
+                "Cached for {}: Food {:?}, Util {:?}, Categories {:?}, Custom {:?}",
+                player.character, data.food.state, data.util.state, data.categories, data.custom
             );
         }
 
@@ -72,9 +81,30 @@ impl Tracker {
         self.players.remove_player(id)
     }
 
+    /// Resets consumable buffs of tracked players once their predicted expiry has passed.
+    pub fn expire_buffs(&mut self, defs: &Definitions, now: u64) {
+        let sorted = self.sorting.slot();
+        let mut changed = false;
+
+        for Entry { player, data } in self.players.iter_mut() {
+            data.expire(
+                now,
+                |id| defs.duration_ms(id),
+                |slot| {
+                    info!("{} expired on {}", slot.name(), player.character);
+                    changed |= sorted == Some(slot);
+                },
+            );
+        }
+
+        if changed {
+            self.refresh_sort();
+        }
+    }
+
     /// Sorts the players in the tracker table.
     fn refresh_sort(&mut self) {
-        match (self.sorting, self.reverse) {
+        match (&self.sorting, self.reverse) {
             (Sorting::Sub, false) => self.players.sort_by_key(|entry| entry.player.subgroup),
             (Sorting::Sub, true) => self
                 .players
@@ -87,15 +117,17 @@ impl Tracker {
                 .players
                 .sort_by(|a, b| Reverse(&a.player.character).cmp(&Reverse(&b.player.character))),
 
-            (Sorting::Food, false) => self.players.sort_by_key(|entry| entry.data.food.state),
-            (Sorting::Food, true) => self
-                .players
-                .sort_by_key(|entry| Reverse(entry.data.food.state)),
-
-            (Sorting::Util, false) => self.players.sort_by_key(|entry| entry.data.util.state),
-            (Sorting::Util, true) => self
-                .players
-                .sort_by_key(|entry| Reverse(entry.data.util.state)),
+            (Sorting::Food | Sorting::Util | Sorting::Category(_), reverse) => {
+                if let Some(slot) = self.sorting.slot() {
+                    if reverse {
+                        self.players
+                            .sort_by_key(|entry| Reverse(entry.data.slot_state(slot)))
+                    } else {
+                        self.players
+                            .sort_by_key(|entry| entry.data.slot_state(slot))
+                    }
+                }
+            }
         }
     }
 
@@ -105,13 +137,40 @@ impl Tracker {
             self.refresh_sort();
         }
     }
+
+    /// Refreshes the sort if sorted by the given consumable slot.
+    pub fn refresh_sort_if_slot(&mut self, slot: Slot) {
+        if self.sorting.slot() == Some(slot) {
+            self.refresh_sort();
+        }
+    }
+
+    /// Refreshes the sort if sorted by any consumable slot.
+    pub fn refresh_sort_if_any_slot(&mut self) {
+        if self.sorting.slot().is_some() {
+            self.refresh_sort();
+        }
+    }
 }
 
 /// Current column sorted by.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Sorting {
     Sub,
     Name,
     Food,
     Util,
+    Category(String),
+}
+
+impl Sorting {
+    /// Returns the consumable slot sorted by, if any.
+    pub fn slot(&self) -> Option<Slot<'_>> {
+        match self {
+            Self::Sub | Self::Name => None,
+            Self::Food => Some(Slot::Food),
+            Self::Util => Some(Slot::Util),
+            Self::Category(name) => Some(Slot::Category(name)),
+        }
+    }
 }
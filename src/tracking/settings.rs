@@ -27,6 +27,12 @@ pub struct TrackerSettings {
     /// Whether to show the subgroup column.
     pub show_sub: bool,
 
+    /// Whether to show the predicted remaining time of food & utility buffs.
+    pub show_remaining: bool,
+
+    /// Whether to show the uptime column.
+    pub show_uptime: bool,
+
     /// Amount of entries displayed before scrolling.
     pub max_entries_displayed: usize,
 
@@ -44,6 +50,8 @@ impl TrackerSettings {
             hotkey: Some(Tracker::DEFAULT_HOTKEY),
             show_icons: true,
             show_sub: true,
+            show_remaining: false,
+            show_uptime: false,
             max_entries_displayed: 10,
             color_sub: Color::Sub,
             color_name: Color::Prof,
@@ -92,29 +100,63 @@ pub struct SettingsEntry {
     #[serde(default)]
     pub util: BuffState<u32>,
 
+    #[serde(default)]
+    pub categories: BTreeMap<String, BuffState<u32>>,
+
     #[serde(default)]
     pub buffs: BTreeMap<u32, BuffState<()>>,
 
+    /// Stack counts of applied custom buffs.
+    #[serde(default)]
+    pub stacks: BTreeMap<u32, u32>,
+
     /// Reinforced state for backwards compatibility.
     #[serde(skip_serializing)]
     pub reinforced: Option<BuffState<()>>,
 }
 
 impl SettingsEntry {
-    pub const fn new(
+    pub fn new(
         player: CachedPlayer,
         food: BuffState<u32>,
         util: BuffState<u32>,
-        buffs: BTreeMap<u32, BuffState<()>>,
+        categories: BTreeMap<String, BuffState<u32>>,
+        custom: BTreeMap<u32, BuffState<u32>>,
     ) -> Self {
+        let stacks = custom
+            .iter()
+            .filter_map(|(id, state)| match state {
+                BuffState::Some(stacks) => Some((*id, *stacks)),
+                _ => None,
+            })
+            .collect();
+        let buffs = custom
+            .into_iter()
+            .map(|(id, state)| (id, state.map(|_| ())))
+            .collect();
         Self {
             player,
             food,
             util,
+            categories,
             buffs,
+            stacks,
             reinforced: None,
         }
     }
+
+    /// Returns the custom buff states with their stack counts.
+    ///
+    /// Entries saved without stack count are loaded as a single stack.
+    pub fn custom_states(&self) -> BTreeMap<u32, BuffState<u32>> {
+        self.buffs
+            .iter()
+            .map(|(id, state)| {
+                let stacks = self.stacks.get(id).copied().unwrap_or(1);
+                (*id, state.map(|_| stacks))
+            })
+            .collect()
+    }
 }
 
 impl From<Entry<Buffs>> for SettingsEntry {
@@ -129,6 +171,10 @@ impl From<(CachedPlayer, Buffs)> for SettingsEntry {
             player,
             data.food.state,
             data.util.state,
+            data.categories
+                .into_iter()
+                .map(|(name, buff)| (name, buff.state))
+                .collect(),
             data.custom
                 .into_iter()
                 .map(|(id, buff)| (id, buff.state))
@@ -144,9 +190,10 @@ impl From<SettingsEntry> for (CachedPlayer, Buffs) {
             entry.buffs.insert(REINFORCED, reinf);
         }
 
+        let custom = entry.custom_states();
         (
             entry.player,
-            Buffs::with_states(entry.food, entry.util, entry.buffs),
+            Buffs::with_states(entry.food, entry.util, entry.categories, custom),
         )
     }
 }
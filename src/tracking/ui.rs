@@ -1,6 +1,8 @@
 use super::{
-    buff::Buffs,
+    buff::{current_time, Buffs, TrackedBuff},
+    history::HistoryBuff,
     settings::{Color, TrackerSettings},
+    uptime::format_percent,
     BuffState, Sorting, Tracker,
 };
 use crate::{
@@ -8,13 +10,13 @@ use crate::{
     buff_ui,
     combo_ui::render_enum_combo,
     data::{
-        DefinitionKind, Definitions, DIMINISHED, MALNOURISHED, NO_BUFF_TEXT, UNKNOWN_BUFF_TEXT,
+        Definitions, SkillNames, Slot, DIMINISHED, MALNOURISHED, NO_BUFF_TEXT, UNKNOWN_BUFF_TEXT,
         UNKNOWN_STATE_TEXT,
     },
     reminder::custom::CustomReminder,
 };
 use arc_util::{
-    colors::{GREEN, RED, YELLOW},
+    colors::{GREEN, GREY, RED, YELLOW},
     tracking::Entry,
     ui::{
         render::{self, TableIconColumn},
@@ -23,11 +25,14 @@ use arc_util::{
 };
 use arcdps::{
     exports::{self, CoreColor},
-    imgui::{TabBar, TabItem, TableColumnFlags, TableFlags, TableSortDirection, Ui},
+    imgui::{
+        self, Condition, TabBar, TabItem, TableColumnFlags, TableColumnSetup, TableFlags,
+        TableSortDirection, Ui,
+    },
     Profession,
 };
 
-pub type Props<'p> = (&'p Definitions, &'p [CustomReminder]);
+pub type Props<'p> = (&'p Definitions, &'p [CustomReminder], &'p SkillNames);
 
 impl Tracker {
     /// Calculates height for a table.
@@ -70,17 +75,100 @@ impl Tracker {
         }
     }
 
+    /// Renders the cell for a consumable slot.
+    fn render_slot_cell(
+        ui: &Ui,
+        defs: &Definitions,
+        names: &SkillNames,
+        settings: &TrackerSettings,
+        colors: &exports::Colors,
+        entry_id: usize,
+        slot: Slot,
+        buff: Option<&TrackedBuff<u32>>,
+    ) {
+        let red = colors.core(CoreColor::LightRed).unwrap_or(RED);
+        let green = colors.core(CoreColor::LightGreen).unwrap_or(GREEN);
+        let yellow = colors.core(CoreColor::LightYellow).unwrap_or(YELLOW);
+
+        match buff.map(|buff| buff.state).unwrap_or_default() {
+            BuffState::Unknown => {
+                ui.text(UNKNOWN_STATE_TEXT);
+                if ui.is_item_hovered() {
+                    ui.tooltip_text("Uncertain");
+                }
+            }
+            BuffState::None => {
+                ui.text_colored(red, NO_BUFF_TEXT);
+                if ui.is_item_hovered() {
+                    ui.tooltip_text(format!("No {}", slot.name()));
+                }
+            }
+            BuffState::Some(buff_id) => {
+                if let Some(data) = defs.definition(buff_id).and_then(|def| def.data()) {
+                    let color = match data.id {
+                        MALNOURISHED | DIMINISHED => red,
+                        _ => green,
+                    };
+                    let remaining = data.duration_ms().and_then(|duration| {
+                        buff.and_then(|buff| buff.remaining(duration, current_time()))
+                    });
+                    ui.group(|| {
+                        ui.text_colored(color, &data.display);
+                        if let (true, Some(remaining)) = (settings.show_remaining, remaining) {
+                            ui.same_line();
+                            ui.text_disabled(buff_ui::format_remaining(remaining));
+                        }
+                    });
+                    buff_ui::render_tracked_buff_tooltip(
+                        ui,
+                        data,
+                        defs.display_language(),
+                        remaining,
+                    );
+                    buff_ui::render_slot_context_menu(
+                        ui,
+                        slot,
+                        entry_id,
+                        data.id,
+                        Some(data),
+                        defs.display_language(),
+                        colors,
+                    );
+                } else {
+                    ui.text_colored(yellow, UNKNOWN_BUFF_TEXT);
+                    if ui.is_item_hovered() {
+                        match names.get(buff_id) {
+                            Some(name) => {
+                                ui.tooltip_text(format!("Unknown {}: {name}", slot.name()))
+                            }
+                            None => ui.tooltip_text(format!("Unknown {}", slot.name())),
+                        }
+                    }
+                    buff_ui::render_slot_context_menu(
+                        ui,
+                        slot,
+                        entry_id,
+                        buff_id,
+                        None,
+                        defs.display_language(),
+                        colors,
+                    );
+                }
+            }
+        }
+    }
+
     /// Renders a player entry in a table.
     fn render_table_entry(
         ui: &Ui,
-        (defs, custom): Props,
+        (defs, custom, names): Props,
         settings: &TrackerSettings,
         colors: &exports::Colors,
         entry: TableEntry,
         show_sub: bool,
         allow_delete: bool,
-    ) -> bool {
-        let mut delete = false;
+    ) -> Option<EntryAction> {
+        let mut action = None;
         let TableEntry { buffs, .. } = entry;
         let sub_color = colors
             .sub_base(entry.subgroup)
@@ -131,100 +219,52 @@ impl Tracker {
             if ui.small_button("Reset buffs") {
                 buffs.reset_buffs();
             }
+            if ui.small_button("Show timeline") {
+                action = Some(EntryAction::Timeline);
+            }
             if allow_delete && ui.small_button("Delete entry") {
-                delete = true;
+                action = Some(EntryAction::Delete);
             }
         });
 
-        // render food cell
-        ui.table_next_column();
-        match buffs.food.state {
-            BuffState::Unknown => {
-                ui.text(UNKNOWN_STATE_TEXT);
-                if ui.is_item_hovered() {
-                    ui.tooltip_text("Uncertain");
-                }
-            }
-            BuffState::None => {
-                ui.text_colored(red, NO_BUFF_TEXT);
-                if ui.is_item_hovered() {
-                    ui.tooltip_text("No Food");
-                }
-            }
-            BuffState::Some(buff_id) => {
-                if let Some(DefinitionKind::Food(food)) = defs.definition(buff_id) {
-                    let color = match food.id {
-                        MALNOURISHED => red,
-                        _ => green,
-                    };
-                    ui.text_colored(color, &food.display);
-                    buff_ui::render_buff_tooltip(ui, food);
-                    buff_ui::render_food_context_menu(
-                        ui,
-                        entry.id,
-                        food.id,
-                        Some(&food.name),
-                        colors,
-                    );
-                } else {
-                    ui.text_colored(yellow, UNKNOWN_BUFF_TEXT);
-                    if ui.is_item_hovered() {
-                        ui.tooltip_text("Unknown Food");
-                    }
-                    buff_ui::render_food_context_menu(ui, entry.id, buff_id, None, colors);
-                }
-            }
-        }
-
-        // render util cell
-        ui.table_next_column();
-        match buffs.util.state {
-            BuffState::Unknown => {
-                ui.text(UNKNOWN_STATE_TEXT);
-                if ui.is_item_hovered() {
-                    ui.tooltip_text("Uncertain");
-                }
-            }
-            BuffState::None => {
-                ui.text_colored(red, NO_BUFF_TEXT);
-                if ui.is_item_hovered() {
-                    ui.tooltip_text("No Utility");
-                }
-            }
-            BuffState::Some(buff_id) => {
-                if let Some(DefinitionKind::Util(util)) = defs.definition(buff_id) {
-                    let color = match util.id {
-                        DIMINISHED => red,
-                        _ => green,
-                    };
-                    ui.text_colored(color, &util.display);
-                    buff_ui::render_buff_tooltip(ui, util);
-                    buff_ui::render_util_context_menu(
-                        ui,
-                        entry.id,
-                        util.id,
-                        Some(&util.name),
-                        colors,
-                    );
-                } else {
-                    ui.text_colored(yellow, UNKNOWN_BUFF_TEXT);
-                    if ui.is_item_hovered() {
-                        ui.tooltip_text("Unknown Utility");
-                    }
-                    buff_ui::render_util_context_menu(ui, entry.id, buff_id, None, colors);
-                }
-            }
+        // render consumable cells
+        let categories = defs
+            .categories()
+            .map(|category| Slot::Category(&category.name));
+        for slot in [Slot::Food, Slot::Util].into_iter().chain(categories) {
+            ui.table_next_column();
+            Self::render_slot_cell(
+                ui,
+                defs,
+                names,
+                settings,
+                colors,
+                entry.id,
+                slot,
+                buffs.slot(slot),
+            );
         }
 
         // render custom buffs cell
         ui.table_next_column();
         ui.group(|| {
             for remind in custom {
-                let short = remind.short();
+                let short = remind.short(names);
                 match buffs.custom_state(remind.id) {
                     BuffState::Unknown => ui.text(short),
                     BuffState::None => ui.text_colored(red, short),
-                    BuffState::Some(_) => ui.text_colored(green, short),
+                    BuffState::Some(stacks) => {
+                        let color = if remind.has_stacks(stacks) {
+                            green
+                        } else {
+                            yellow
+                        };
+                        if stacks > 1 {
+                            ui.text_colored(color, format!("{short}{stacks}"));
+                        } else {
+                            ui.text_colored(color, short);
+                        }
+                    }
                 }
                 ui.same_line_with_spacing(0.0, 0.0);
             }
@@ -232,17 +272,48 @@ impl Tracker {
         if ui.is_item_hovered() {
             ui.tooltip(|| {
                 for remind in custom {
-                    let name = remind.display_name();
+                    let name = remind.resolved_name(names);
                     match buffs.custom_state(remind.id) {
                         BuffState::Unknown => ui.text(name),
                         BuffState::None => ui.text_colored(red, name),
-                        BuffState::Some(_) => ui.text_colored(green, name),
+                        BuffState::Some(stacks) if remind.has_stacks(stacks) => {
+                            ui.text_colored(green, format!("{name} x{stacks}"))
+                        }
+                        BuffState::Some(stacks) => ui.text_colored(
+                            yellow,
+                            format!("{name} x{stacks} (min {})", remind.min_stacks),
+                        ),
                     }
                 }
             });
         }
 
-        delete
+        // render uptime cell
+        if settings.show_uptime {
+            ui.table_next_column();
+            let (encounter, session) = buffs.current_uptime(current_time());
+            ui.text(format!(
+                "{}/{}",
+                format_percent(encounter.food_percent()),
+                format_percent(encounter.util_percent())
+            ));
+            if ui.is_item_hovered() {
+                ui.tooltip(|| {
+                    buff_ui::render_uptime_tooltip(
+                        ui,
+                        "Encounter",
+                        &encounter,
+                        defs,
+                        custom,
+                        names,
+                    );
+                    ui.spacing();
+                    buff_ui::render_uptime_tooltip(ui, "Session", &session, defs, custom, names);
+                });
+            }
+        }
+
+        action
     }
 
     /// Renders the tracker tab for the squad.
@@ -250,8 +321,9 @@ impl Tracker {
         if self.players.is_empty() {
             ui.text("No players in range");
         } else {
+            let (defs, ..) = props;
             let show_sub = self.settings.show_sub;
-            let columns = [
+            let mut columns = vec![
                 TableIconColumn::with_flags(
                     "Sub",
                     None,
@@ -272,13 +344,31 @@ impl Tracker {
                     UTIL_ICON.as_ref(),
                     TableColumnFlags::PREFER_SORT_DESCENDING,
                 ),
+            ];
+            columns.extend(defs.categories().map(|category| {
                 TableIconColumn::with_flags(
-                    "Buffs",
-                    UNKNOWN_ICON.as_ref(),
+                    category.display_name(),
+                    None,
+                    TableColumnFlags::PREFER_SORT_DESCENDING,
+                )
+            }));
+            columns.push(TableIconColumn::with_flags(
+                "Buffs",
+                UNKNOWN_ICON.as_ref(),
+                TableColumnFlags::NO_SORT,
+            ));
+            if self.settings.show_uptime {
+                columns.push(TableIconColumn::with_flags(
+                    "Uptime",
+                    None,
                     TableColumnFlags::NO_SORT,
-                ),
-            ];
-            let columns = if show_sub { &columns } else { &columns[1..] };
+                ));
+            }
+            let columns = if show_sub {
+                &columns[..]
+            } else {
+                &columns[1..]
+            };
 
             if let Some(_table) = render::table_with_icons_sizing(
                 ui,
@@ -306,7 +396,11 @@ impl Tracker {
                                 1 => Sorting::Name,
                                 2 => Sorting::Food,
                                 3 => Sorting::Util,
-                                _ => unreachable!("column sort spec index out of range"),
+                                index => defs
+                                    .categories()
+                                    .nth(index - 4)
+                                    .map(|category| Sorting::Category(category.name.to_string()))
+                                    .unwrap_or(Sorting::Name),
                             };
 
                             // ascending is reverse order for us
@@ -320,8 +414,9 @@ impl Tracker {
 
                 // render table content
                 let colors = exports::colors();
+                let mut timeline = None;
                 for entry in self.players.iter_mut() {
-                    Self::render_table_entry(
+                    let action = Self::render_table_entry(
                         ui,
                         props,
                         &self.settings,
@@ -330,6 +425,12 @@ impl Tracker {
                         show_sub,
                         false,
                     );
+                    if let Some(EntryAction::Timeline) = action {
+                        timeline = Some(entry.player.character.clone());
+                    }
+                }
+                if timeline.is_some() {
+                    self.timeline = timeline;
                 }
             }
         }
@@ -337,20 +438,30 @@ impl Tracker {
 
     /// Renders the tracker tab for own characters.
     fn render_characters_tab(&mut self, ui: &Ui, props: Props) {
+        let (defs, ..) = props;
         let current = self.players.get_self();
         let count = self.players.cache_len() + if current.is_some() { 1 } else { 0 };
 
+        let mut columns = vec![
+            TableIconColumn::new("Player", None),
+            TableIconColumn::new("Food", FOOD_ICON.as_ref()),
+            TableIconColumn::new("Util", UTIL_ICON.as_ref()),
+        ];
+        columns.extend(
+            defs.categories()
+                .map(|category| TableIconColumn::new(category.display_name(), None)),
+        );
+        columns.push(TableIconColumn::new("Buffs", UNKNOWN_ICON.as_ref()));
+        if self.settings.show_uptime {
+            columns.push(TableIconColumn::new("Uptime", None));
+        }
+
         if current.is_none() && !self.players.cached() {
             ui.text("No characters found");
         } else if let Some(_table) = render::table_with_icons_sizing(
             ui,
             "##self-table",
-            &[
-                TableIconColumn::new("Player", None),
-                TableIconColumn::new("Food", FOOD_ICON.as_ref()),
-                TableIconColumn::new("Util", UTIL_ICON.as_ref()),
-                TableIconColumn::new("Buffs", UNKNOWN_ICON.as_ref()),
-            ],
+            &columns,
             TableFlags::SIZING_STRETCH_PROP | TableFlags::PAD_OUTER_X | TableFlags::SCROLL_Y,
             self.settings.show_icons,
             [0.0, self.table_height(ui, count)],
@@ -358,8 +469,9 @@ impl Tracker {
         ) {
             // render table content
             let colors = exports::colors();
+            let mut timeline = None;
             if let Some(entry) = self.players.get_self_mut() {
-                Self::render_table_entry(
+                let action = Self::render_table_entry(
                     ui,
                     props,
                     &self.settings,
@@ -368,10 +480,13 @@ impl Tracker {
                     false,
                     false,
                 );
+                if let Some(EntryAction::Timeline) = action {
+                    timeline = Some(entry.player.character.clone());
+                }
             }
             let mut delete = None;
             for (i, (player, buffs)) in self.players.cache_iter_mut().enumerate() {
-                let should_delete = Self::render_table_entry(
+                let action = Self::render_table_entry(
                     ui,
                     props,
                     &self.settings,
@@ -387,13 +502,213 @@ impl Tracker {
                     false,
                     true,
                 );
-                if should_delete {
-                    delete = Some(player.character.clone());
+                match action {
+                    Some(EntryAction::Delete) => delete = Some(player.character.clone()),
+                    Some(EntryAction::Timeline) => timeline = Some(player.character.clone()),
+                    None => {}
                 }
             }
             if let Some(name) = delete {
                 self.players.remove_cache_entry(name);
             }
+            if timeline.is_some() {
+                self.timeline = timeline;
+            }
+        }
+    }
+
+    /// Renders the session summary tab with uptime stats of the squad.
+    fn render_session_tab(&mut self, ui: &Ui, (defs, custom, names): Props) {
+        if self.players.is_empty() {
+            ui.text("No players in range");
+            return;
+        }
+
+        let now = current_time();
+        let columns = ["Player", "Combat", "Food", "Util"]
+            .into_iter()
+            .chain(defs.categories().map(|category| category.display_name()))
+            .chain(custom.iter().map(|remind| remind.resolved_name(names)))
+            .collect::<Vec<_>>();
+
+        if let Some(_table) = ui.begin_table_with_sizing(
+            "##session-table",
+            columns.len(),
+            TableFlags::SIZING_STRETCH_PROP | TableFlags::PAD_OUTER_X | TableFlags::SCROLL_Y,
+            [0.0, self.table_height(ui, self.players.len())],
+            0.0,
+        ) {
+            for column in columns {
+                ui.table_setup_column(column);
+            }
+            ui.table_headers_row();
+
+            let colors = exports::colors();
+            for entry in self.players.iter() {
+                let (_, session) = entry.data.current_uptime(now);
+
+                ui.table_next_row();
+
+                ui.table_next_column();
+                match colors.prof_base(entry.player.profession) {
+                    Some(color) => {
+                        ui.text_colored(render::with_alpha(color, 1.0), &entry.player.character)
+                    }
+                    None => ui.text(&entry.player.character),
+                }
+                if ui.is_item_hovered() {
+                    ui.tooltip_text(&entry.player.account);
+                }
+
+                ui.table_next_column();
+                ui.text(buff_ui::format_remaining(session.duration));
+
+                ui.table_next_column();
+                ui.text(format_percent(session.food_percent()));
+
+                ui.table_next_column();
+                ui.text(format_percent(session.util_percent()));
+
+                for category in defs.categories() {
+                    ui.table_next_column();
+                    ui.text(format_percent(session.category_percent(&category.name)));
+                }
+
+                for remind in custom {
+                    ui.table_next_column();
+                    ui.text(format_percent(session.custom_percent(remind.id)));
+                }
+            }
+        }
+
+        if ui.button("Reset session") {
+            for entry in self.players.iter_mut() {
+                entry.data.uptime.reset();
+            }
+        }
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Reset the uptime stats of all players.");
+        }
+    }
+
+    /// Returns the buffs of the player or own cached character with the given name.
+    fn find_buffs(&mut self, character: &str) -> Option<&mut Buffs> {
+        if self
+            .players
+            .iter()
+            .any(|entry| entry.player.character == character)
+        {
+            self.players
+                .iter_mut()
+                .find(|entry| entry.player.character == character)
+                .map(|entry| &mut entry.data)
+        } else {
+            self.players
+                .cache_iter_mut()
+                .find(|(player, _)| player.character == character)
+                .map(|(_, buffs)| buffs)
+        }
+    }
+
+    /// Renders the buff timeline window for the selected player.
+    fn render_timeline(&mut self, ui: &Ui, (defs, custom, names): Props) {
+        let Some(character) = self.timeline.clone() else {
+            return;
+        };
+
+        let colors = exports::colors();
+        let grey = colors.core(CoreColor::MediumGrey).unwrap_or(GREY);
+        let red = colors.core(CoreColor::LightRed).unwrap_or(RED);
+        let green = colors.core(CoreColor::LightGreen).unwrap_or(GREEN);
+        let yellow = colors.core(CoreColor::LightYellow).unwrap_or(YELLOW);
+        let now = current_time();
+
+        let mut open = true;
+        imgui::Window::new(format!("Timeline {character}##food-reminder-timeline"))
+            .opened(&mut open)
+            .collapsible(false)
+            .size([400.0, 250.0], Condition::FirstUseEver)
+            .build(ui, || {
+                let Some(buffs) = self.find_buffs(&character) else {
+                    ui.text("Player no longer tracked");
+                    return;
+                };
+                if buffs.history.is_empty() {
+                    ui.text("No buff changes recorded");
+                    return;
+                }
+
+                if ui.small_button("Clear") {
+                    buffs.history.clear();
+                }
+
+                if let Some(_table) = ui.begin_table_header_with_sizing(
+                    "##timeline-table",
+                    [
+                        TableColumnSetup::new("Time"),
+                        TableColumnSetup::new("Buff"),
+                        TableColumnSetup::new("Change"),
+                        TableColumnSetup::new("Encounter"),
+                    ],
+                    TableFlags::SIZING_STRETCH_PROP
+                        | TableFlags::PAD_OUTER_X
+                        | TableFlags::SCROLL_Y,
+                    [0.0, 0.0],
+                    0.0,
+                ) {
+                    for change in buffs.history.iter().rev() {
+                        ui.table_next_row();
+
+                        ui.table_next_column();
+                        ui.text(format!(
+                            "{} ago",
+                            buff_ui::format_remaining(now.saturating_sub(change.time))
+                        ));
+
+                        ui.table_next_column();
+                        match &change.buff {
+                            HistoryBuff::Food => ui.text("Food"),
+                            HistoryBuff::Util => ui.text("Utility"),
+                            HistoryBuff::Category(name) => ui.text(name),
+                            HistoryBuff::Custom(id) => {
+                                match custom.iter().find(|remind| remind.id == *id) {
+                                    Some(remind) => ui.text(remind.resolved_name(names)),
+                                    None => ui.text(names.get(*id).unwrap_or("Custom")),
+                                }
+                            }
+                        }
+
+                        ui.table_next_column();
+                        match (&change.buff, change.state) {
+                            (_, BuffState::Unknown) => ui.text(UNKNOWN_STATE_TEXT),
+                            (_, BuffState::None) => ui.text_colored(red, "Dropped"),
+                            (HistoryBuff::Custom(_), BuffState::Some(_)) => {
+                                ui.text_colored(green, "Applied")
+                            }
+                            (_, BuffState::Some(id)) => {
+                                match defs.definition(id).and_then(|def| def.data()) {
+                                    Some(data) => ui
+                                        .text_colored(green, data.name_in(defs.display_language())),
+                                    None => ui.text_colored(
+                                        yellow,
+                                        names.get(id).unwrap_or(UNKNOWN_BUFF_TEXT),
+                                    ),
+                                }
+                            }
+                        }
+
+                        ui.table_next_column();
+                        if change.encounter {
+                            ui.text("Yes");
+                        } else {
+                            ui.text_colored(grey, "No");
+                        }
+                    }
+                }
+            });
+
+        if !open {
+            self.timeline = None;
         }
     }
 
@@ -424,17 +739,23 @@ impl Component<Props<'_>> for Tracker {
                 self.render_characters_tab(ui, props);
             });
 
+            TabItem::new("Session").build(ui, || {
+                self.render_session_tab(ui, props);
+            });
+
             TabItem::new("Builds").build(ui, || {
                 self.render_builds_tab(ui, props);
             })
         });
+
+        self.render_timeline(ui, props);
     }
 }
 
 impl Windowable<Props<'_>> for Tracker {
     const CONTEXT_MENU: bool = true;
 
-    fn render_menu(&mut self, ui: &Ui, _: &mut (&Definitions, &[CustomReminder])) {
+    fn render_menu(&mut self, ui: &Ui, _: &mut Props) {
         let colors = exports::colors();
         let grey = colors
             .core(CoreColor::MediumGrey)
@@ -457,6 +778,8 @@ impl Windowable<Props<'_>> for Tracker {
             // table column checkboxes
             ui.checkbox("Show icons", &mut self.settings.show_icons);
             ui.checkbox("Show subgroup", &mut self.settings.show_sub);
+            ui.checkbox("Show remaining time", &mut self.settings.show_remaining);
+            ui.checkbox("Show uptime", &mut self.settings.show_uptime);
             ui.checkbox("Show build notes", &mut self.builds.display_notes);
 
             let input_width = render::ch_width(ui, 16);
@@ -498,3 +821,10 @@ impl<'a> TableEntry<'a> {
         }
     }
 }
+
+/// Action requested from the context menu of a table entry.
+#[derive(Debug)]
+enum EntryAction {
+    Delete,
+    Timeline,
+}
@@ -0,0 +1,309 @@
+use std::collections::BTreeMap;
+
+/// Uptime of buffs during encounters.
+#[derive(Debug, Clone)]
+pub struct Uptime {
+    /// Uptime in the current or last encounter.
+    pub encounter: UptimeStats,
+
+    /// Uptime in all encounters of the session.
+    pub session: UptimeStats,
+
+    /// Start time of the current or last encounter.
+    started: Option<u64>,
+
+    /// Time of the last update, set while in an encounter.
+    last: Option<u64>,
+}
+
+impl Uptime {
+    /// Creates a new empty uptime.
+    pub const fn new() -> Self {
+        Self {
+            encounter: UptimeStats::new(),
+            session: UptimeStats::new(),
+            started: None,
+            last: None,
+        }
+    }
+
+    /// Checks whether an encounter is in progress.
+    pub fn in_encounter(&self) -> bool {
+        self.last.is_some()
+    }
+
+    /// Returns the start time of the current or last encounter.
+    pub fn started(&self) -> Option<u64> {
+        self.started
+    }
+
+    /// Starts a new encounter at the given time.
+    ///
+    /// An encounter still in progress is dropped without accounting the time since its last update.
+    pub fn start(&mut self, time: u64) {
+        self.encounter = UptimeStats::new();
+        self.started = Some(time);
+        self.last = Some(time);
+    }
+
+    /// Ends the current encounter at the given time.
+    pub fn end(&mut self, time: u64, present: &Present) {
+        self.update(time, present);
+        self.last = None;
+    }
+
+    /// Accounts the time since the last update with the buffs present until now.
+    ///
+    /// Has no effect outside of encounters.
+    pub fn update(&mut self, time: u64, present: &Present) {
+        if let Some(last) = self.last {
+            let elapsed = time.saturating_sub(last);
+            self.encounter.add(elapsed, present);
+            self.session.add(elapsed, present);
+            self.last = Some(last.max(time));
+        }
+    }
+
+    /// Returns the encounter & session uptime including the time since the last update.
+    pub fn current(&self, now: u64, present: &Present) -> (UptimeStats, UptimeStats) {
+        let mut uptime = self.clone();
+        uptime.update(now, present);
+        (uptime.encounter, uptime.session)
+    }
+
+    /// Resets the session uptime.
+    pub fn reset(&mut self) {
+        self.encounter = UptimeStats::new();
+        self.session = UptimeStats::new();
+        self.started = None;
+    }
+}
+
+impl Default for Uptime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Buffs present on a player.
+///
+/// [`None`] marks buffs with unknown state.
+#[derive(Debug, Clone, Default)]
+pub struct Present {
+    pub food: Option<bool>,
+    pub util: Option<bool>,
+
+    /// Consumable category buffs with known state by category name.
+    pub categories: BTreeMap<String, bool>,
+
+    /// Custom buffs with known state by id.
+    pub custom: BTreeMap<u32, bool>,
+}
+
+/// Accumulated encounter durations with buffs present.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UptimeStats {
+    /// Total encounter duration in milliseconds.
+    pub duration: u64,
+
+    /// Uptime of the food buff.
+    pub food: BuffUptime,
+
+    /// Uptime of the utility buff.
+    pub util: BuffUptime,
+
+    /// Uptime of consumable category buffs by category name.
+    pub categories: BTreeMap<String, BuffUptime>,
+
+    /// Uptime of custom buffs by id.
+    pub custom: BTreeMap<u32, BuffUptime>,
+}
+
+impl UptimeStats {
+    /// Creates new empty uptime stats.
+    pub const fn new() -> Self {
+        Self {
+            duration: 0,
+            food: BuffUptime::new(),
+            util: BuffUptime::new(),
+            categories: BTreeMap::new(),
+            custom: BTreeMap::new(),
+        }
+    }
+
+    /// Adds an elapsed duration with the given buffs present.
+    fn add(&mut self, elapsed: u64, present: &Present) {
+        self.duration += elapsed;
+        self.food.add(elapsed, present.food);
+        self.util.add(elapsed, present.util);
+        for (name, present) in &present.categories {
+            self.categories
+                .entry(name.clone())
+                .or_default()
+                .add(elapsed, Some(*present));
+        }
+        for (id, present) in &present.custom {
+            self.custom
+                .entry(*id)
+                .or_default()
+                .add(elapsed, Some(*present));
+        }
+    }
+
+    /// Returns the food uptime percentage.
+    pub fn food_percent(&self) -> Option<f32> {
+        self.food.percent()
+    }
+
+    /// Returns the utility uptime percentage.
+    pub fn util_percent(&self) -> Option<f32> {
+        self.util.percent()
+    }
+
+    /// Returns the uptime percentage of a consumable category.
+    pub fn category_percent(&self, name: &str) -> Option<f32> {
+        self.categories.get(name).and_then(BuffUptime::percent)
+    }
+
+    /// Returns the uptime percentage of a custom buff.
+    pub fn custom_percent(&self, id: u32) -> Option<f32> {
+        self.custom.get(&id).and_then(BuffUptime::percent)
+    }
+}
+
+impl Default for UptimeStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Accumulated durations of a single buff.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BuffUptime {
+    /// Duration with the buff present in milliseconds.
+    pub present: u64,
+
+    /// Duration with the buff state known in milliseconds.
+    pub known: u64,
+}
+
+impl BuffUptime {
+    /// Creates a new empty buff uptime.
+    pub const fn new() -> Self {
+        Self {
+            present: 0,
+            known: 0,
+        }
+    }
+
+    /// Adds an elapsed duration with the given buff presence.
+    ///
+    /// Durations with unknown state are left out.
+    fn add(&mut self, elapsed: u64, present: Option<bool>) {
+        if let Some(present) = present {
+            self.known += elapsed;
+            if present {
+                self.present += elapsed;
+            }
+        }
+    }
+
+    /// Returns the percentage of the known duration with the buff present.
+    ///
+    /// Returns [`None`] without any known duration.
+    pub fn percent(&self) -> Option<f32> {
+        (self.known > 0).then(|| 100.0 * self.present as f32 / self.known as f32)
+    }
+}
+
+/// Formats an optional uptime percentage for display.
+pub fn format_percent(percent: Option<f32>) -> String {
+    match percent {
+        Some(percent) => format!("{percent:.0}%"),
+        None => "-".into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn present(food: Option<bool>, util: Option<bool>, custom: &[(u32, bool)]) -> Present {
+        Present {
+            food,
+            util,
+            categories: BTreeMap::new(),
+            custom: custom.iter().copied().collect(),
+        }
+    }
+
+    #[test]
+    fn uptime() {
+        let mut uptime = Uptime::new();
+        assert!(!uptime.in_encounter());
+
+        // updates outside of encounters are ignored
+        uptime.update(100, &present(Some(true), Some(true), &[]));
+        assert_eq!(uptime.session, UptimeStats::new());
+
+        uptime.start(1000);
+        assert!(uptime.in_encounter());
+        assert_eq!(uptime.started(), Some(1000));
+        uptime.update(2000, &present(Some(true), Some(false), &[(1, true)]));
+        uptime.end(4000, &present(Some(false), Some(false), &[(1, true)]));
+        assert!(!uptime.in_encounter());
+
+        let stats = &uptime.encounter;
+        assert_eq!(stats.duration, 3000);
+        assert_eq!(stats.food_percent(), Some(100.0 / 3.0));
+        assert_eq!(stats.util_percent(), Some(0.0));
+        assert_eq!(stats.custom_percent(1), Some(100.0));
+        assert_eq!(stats.custom_percent(2), None);
+
+        // current uptime includes the time since the last update
+        uptime.start(10_000);
+        assert_eq!(uptime.encounter, UptimeStats::new());
+        let (encounter, session) = uptime.current(11_000, &present(Some(true), None, &[]));
+        assert_eq!(encounter.duration, 1000);
+        assert_eq!(session.duration, 4000);
+        assert_eq!(session.food_percent(), Some(50.0));
+        assert_eq!(uptime.session.duration, 3000);
+
+        // restarting does not account the time of the unfinished encounter
+        uptime.start(100_000);
+        uptime.end(101_000, &present(Some(true), Some(true), &[]));
+        assert_eq!(uptime.encounter.duration, 1000);
+        assert_eq!(uptime.session.duration, 4000);
+
+        uptime.reset();
+        assert_eq!(uptime.session, UptimeStats::new());
+    }
+
+    #[test]
+    fn unknown_excluded() {
+        let mut stats = UptimeStats::new();
+        stats.add(1000, &present(None, None, &[]));
+        assert_eq!(stats.duration, 1000);
+        assert_eq!(stats.food_percent(), None);
+        assert_eq!(stats.util_percent(), None);
+
+        stats.add(1000, &present(Some(true), Some(false), &[]));
+        stats.add(3000, &present(None, Some(true), &[]));
+        assert_eq!(stats.duration, 5000);
+        assert_eq!(stats.food_percent(), Some(100.0));
+        assert_eq!(stats.util_percent(), Some(75.0));
+    }
+
+    #[test]
+    fn categories() {
+        let mut present = present(Some(true), Some(true), &[]);
+        let mut stats = UptimeStats::new();
+        stats.add(1000, &present);
+        present.categories.insert("Infusion".into(), false);
+        stats.add(1000, &present);
+        present.categories.insert("Infusion".into(), true);
+        stats.add(3000, &present);
+        assert_eq!(stats.category_percent("Infusion"), Some(75.0));
+        assert_eq!(stats.category_percent("Sigil"), None);
+    }
+}
@@ -1,13 +1,273 @@
 use jsonc_parser::ParseOptions;
 use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::fmt;
+
+/// Parses a JSONC value from an input string.
+pub fn parse_jsonc_value(input: &str) -> Result<Value, JsonError> {
+    match jsonc_parser::parse_to_serde_value(input, &ParseOptions::default()) {
+        Ok(Some(value)) => Ok(value),
+        Ok(None) => Err(JsonError::Syntax {
+            line: 1,
+            column: 1,
+            message: "Empty input".into(),
+        }),
+        Err(err) => Err(JsonError::Syntax {
+            line: err.line_display(),
+            column: err.column_display(),
+            message: err.kind().to_string(),
+        }),
+    }
+}
 
 /// Parses JSONC from an input string.
-pub fn parse_jsonc<T>(input: &str) -> Option<T>
+pub fn parse_jsonc<T>(input: &str) -> Result<T, JsonError>
 where
     T: DeserializeOwned,
 {
-    jsonc_parser::parse_to_serde_value(input, &ParseOptions::default())
-        .ok()
-        .and_then(|value| value)
-        .and_then(|value| serde_json::from_value(value).ok())
+    let value = parse_jsonc_value(input)?;
+    serde_json::from_value(value).map_err(|err| JsonError::Data(err.to_string()))
+}
+
+/// Finds the line & column of the value at the given path in a JSONC input.
+///
+/// Paths consist of object keys & array indices, e.g. `categories[1].buffs[0]`.
+/// Returns [`None`] if the path is not present or the input is malformed.
+pub fn locate_jsonc(input: &str, path: &str) -> Option<(usize, usize)> {
+    let mut scanner = JsoncScanner { input, pos: 0 };
+    scanner.skip_trivia();
+    for part in path.split('.') {
+        let (key, indices) = part.split_once('[').unwrap_or((part, ""));
+        if !key.is_empty() {
+            scanner.enter_key(key)?;
+        }
+        for index in indices.split('[') {
+            if let Some(index) = index.strip_suffix(']') {
+                scanner.enter_index(index.parse().ok()?)?;
+            }
+        }
+    }
+
+    let before = &input[..scanner.pos];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let line = before.matches('\n').count() + 1;
+    let column = before[line_start..].chars().count() + 1;
+    Some((line, column))
+}
+
+/// Checks whether a JSONC input contains comments outside of strings.
+pub fn has_jsonc_comments(input: &str) -> bool {
+    let mut quote = None;
+    let mut escaped = false;
+    let mut chars = input.chars().peekable();
+    while let Some(char) = chars.next() {
+        match quote {
+            Some(_) if escaped => escaped = false,
+            Some(_) if char == '\\' => escaped = true,
+            Some(end) if char == end => quote = None,
+            Some(_) => {}
+            None if char == '"' || char == '\'' => quote = Some(char),
+            None if char == '/' && matches!(chars.peek(), Some('/' | '*')) => return true,
+            None => {}
+        }
+    }
+    false
+}
+
+/// Scanner for navigating JSONC input without parsing values.
+struct JsoncScanner<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> JsoncScanner<'a> {
+    /// Returns the remaining input.
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    /// Advances past the given byte if next.
+    fn eat(&mut self, byte: u8) -> bool {
+        let found = self.rest().as_bytes().first() == Some(&byte);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    /// Skips whitespace & comments.
+    fn skip_trivia(&mut self) {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            if trimmed.starts_with("//") {
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else if let Some(comment) = trimmed.strip_prefix("/*") {
+                self.pos += comment.find("*/").map_or(trimmed.len(), |end| end + 4);
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Skips a string literal, returning its raw content.
+    fn skip_string(&mut self) -> Option<&'a str> {
+        let rest = self.rest();
+        let quote = rest.chars().next()?;
+        let mut escaped = false;
+        for (i, char) in rest.char_indices().skip(1) {
+            if escaped {
+                escaped = false;
+            } else if char == '\\' {
+                escaped = true;
+            } else if char == quote {
+                self.pos += i + 1;
+                return Some(&rest[1..i]);
+            }
+        }
+        None
+    }
+
+    /// Skips an object key, returning its raw content.
+    fn skip_key(&mut self) -> Option<&'a str> {
+        let rest = self.rest();
+        if rest.starts_with(['"', '\'']) {
+            self.skip_string()
+        } else {
+            let end = rest.find(|char: char| char == ':' || char.is_whitespace())?;
+            self.pos += end;
+            Some(&rest[..end])
+        }
+    }
+
+    /// Skips a complete value including nested objects & arrays.
+    fn skip_value(&mut self) -> Option<()> {
+        let rest = self.rest();
+        if rest.starts_with(['"', '\'']) {
+            self.skip_string()?;
+        } else if rest.starts_with('{') {
+            self.pos += 1;
+            while self.next_entry(b'}')? {
+                self.skip_key()?;
+                self.skip_trivia();
+                self.eat(b':').then_some(())?;
+                self.skip_trivia();
+                self.skip_value()?;
+            }
+        } else if rest.starts_with('[') {
+            self.pos += 1;
+            while self.next_entry(b']')? {
+                self.skip_value()?;
+            }
+        } else {
+            let end = rest
+                .find(|char: char| matches!(char, ',' | ']' | '}' | '/') || char.is_whitespace())
+                .unwrap_or(rest.len());
+            (end > 0).then_some(())?;
+            self.pos += end;
+        }
+        Some(())
+    }
+
+    /// Moves to the next entry of an object or array with the given closing byte.
+    ///
+    /// Returns `false` once the end is reached.
+    fn next_entry(&mut self, close: u8) -> Option<bool> {
+        self.skip_trivia();
+        self.eat(b',');
+        self.skip_trivia();
+        if self.eat(close) {
+            return Some(false);
+        }
+        (!self.rest().is_empty()).then_some(true)
+    }
+
+    /// Moves into the value of the given key of the current object.
+    fn enter_key(&mut self, key: &str) -> Option<()> {
+        self.eat(b'{').then_some(())?;
+        while self.next_entry(b'}')? {
+            let found = self.skip_key()? == key;
+            self.skip_trivia();
+            self.eat(b':').then_some(())?;
+            self.skip_trivia();
+            if found {
+                return Some(());
+            }
+            self.skip_value()?;
+        }
+        None
+    }
+
+    /// Moves into the element at the given index of the current array.
+    fn enter_index(&mut self, index: usize) -> Option<()> {
+        self.eat(b'[').then_some(())?;
+        let mut current = 0;
+        while self.next_entry(b']')? {
+            if current == index {
+                return Some(());
+            }
+            self.skip_value()?;
+            current += 1;
+        }
+        None
+    }
+}
+
+/// Encodes an ingame chat link for the given item id.
+pub fn item_chat_link(item_id: u32, count: u8) -> String {
+    // header, count, 3 byte item id, flags
+    let [id0, id1, id2, _] = item_id.to_le_bytes();
+    let bytes = [0x02, count, id0, id1, id2, 0x00];
+    format!("[&{}]", base64_encode(&bytes))
+}
+
+/// Encodes bytes as standard base64 with padding.
+fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut result = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = u32::from(b[0]) << 16 | u32::from(b[1]) << 8 | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                result.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                result.push('=');
+            }
+        }
+    }
+    result
+}
+
+/// Error when parsing JSONC.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum JsonError {
+    /// Input is not valid JSONC.
+    Syntax {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+
+    /// Input does not match the expected structure.
+    Data(String),
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax {
+                line,
+                column,
+                message,
+            } => write!(f, "{message} at line {line} column {column}"),
+            Self::Data(message) => f.write_str(message),
+        }
+    }
 }